@@ -0,0 +1,337 @@
+//! Derive macros for `cairo_vm_base::cairo_type::CairoWritable` and its counterpart
+//! `CairoReadable`, defined in `mmr_header_accumulator_hints::types::readable`.
+//!
+//! Fields are written in declaration order, matching the member order of the Cairo struct:
+//!
+//! - `Vec<T>` fields are written to a new segment and take a single pointer field.
+//! - Any other field is written in place with its `CairoType` impl, for `Felt` and `Uint256`, or
+//!   its `CairoWritable` impl, for nested structs, and takes its `n_fields()`.
+//!
+//! Attributes:
+//!
+//! - `#[cairo(with_len = "name")]` on a `Vec<T>` field also writes its length right after the
+//!   pointer, for Cairo structs with an explicit length member such as `peaks_len`. Without a
+//!   name, the member is called `<field>_len`.
+//! - `#[cairo(check = "method")]` on the struct calls `self.method()` before writing. The method
+//!   returns a `Result<(), E>` with `HintError: From<E>`.
+//! - `#[cairo(readable = "path")]` on the struct gives the path of the module defining
+//!   `CairoReadable`, `read_array` and `read_len`. It defaults to `crate::types::readable`, so
+//!   other crates set it to `::mmr_header_accumulator_hints::types::readable`.
+//!
+//! The derive also generates an inherent `cairo_members()` listing the name and size of each
+//! member of the Cairo struct, in order, to check the layout against the compiled program.
+//!
+//! `CairoReadable` reads the same layout back. A `Vec<T>` field without a length member takes the
+//! length of the next one, as `keccak_peaks` shares `peaks_len` with `poseidon_peaks` below.
+//! `check` is ignored.
+//!
+//! ```ignore
+//! #[derive(CairoWritable, CairoReadable)]
+//! #[cairo(check = "check_peaks_len")]
+//! pub struct MmrSnapshotCairo {
+//!     pub keccak_root: Uint256,
+//!     pub poseidon_root: Felt,
+//!     pub elements_count: Felt,
+//!     pub keccak_peaks: Vec<Uint256>,
+//!     #[cairo(with_len = "peaks_len")]
+//!     pub poseidon_peaks: Vec<Felt>,
+//! }
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Fields, GenericArgument, Ident, LitStr, Path,
+    PathArguments, Type,
+};
+
+#[proc_macro_derive(CairoWritable, attributes(cairo))]
+pub fn derive_cairo_writable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let StructAttributes { check, .. } = parse_struct_attributes(&input.attrs)?;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "CairoWritable can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "CairoWritable can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut writes = Vec::new();
+    let mut sizes = Vec::new();
+    let mut members = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named fields have an ident");
+        let with_len = parse_field_attributes(&field.attrs)?;
+        let member = ident.to_string();
+
+        if is_vec(&field.ty) {
+            writes.push(write_vec(ident));
+            sizes.push(quote! { 1usize });
+            members.push(quote! { (#member, 1usize) });
+            if let Some(len_member) = with_len {
+                let len_member = len_member.unwrap_or_else(|| format!("{member}_len"));
+                writes.push(write_len(ident));
+                sizes.push(quote! { 1usize });
+                members.push(quote! { (#len_member, 1usize) });
+            }
+        } else if with_len.is_some() {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "#[cairo(with_len)] can only be used on Vec fields",
+            ));
+        } else {
+            let ty = &field.ty;
+            writes.push(quote! {
+                let address = self.#ident.to_memory(vm, address)?;
+            });
+            sizes.push(quote! { <#ty>::n_fields() });
+            members.push(quote! { (#member, <#ty>::n_fields()) });
+        }
+    }
+
+    let check = check.map(|method| quote! { self.#method()?; });
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::cairo_vm_base::cairo_type::CairoWritable for #name #ty_generics #where_clause {
+            fn to_memory(
+                &self,
+                vm: &mut ::cairo_vm_base::vm::cairo_vm::vm::vm_core::VirtualMachine,
+                address: ::cairo_vm_base::vm::cairo_vm::types::relocatable::Relocatable,
+            ) -> ::core::result::Result<
+                ::cairo_vm_base::vm::cairo_vm::types::relocatable::Relocatable,
+                ::cairo_vm_base::vm::cairo_vm::vm::errors::hint_errors::HintError,
+            > {
+                #[allow(unused_imports)]
+                use ::cairo_vm_base::cairo_type::{CairoType as _, CairoWritable as _};
+
+                #check
+                #(#writes)*
+                ::core::result::Result::Ok(address)
+            }
+
+            fn n_fields() -> usize {
+                #[allow(unused_imports)]
+                use ::cairo_vm_base::cairo_type::{CairoType as _, CairoWritable as _};
+
+                0usize #(+ #sizes)*
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Names and sizes of the members of the Cairo struct, in memory order.
+            pub fn cairo_members() -> ::std::vec::Vec<(&'static str, usize)> {
+                #[allow(unused_imports)]
+                use ::cairo_vm_base::cairo_type::{CairoType as _, CairoWritable as _};
+
+                ::std::vec![#(#members),*]
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(CairoReadable, attributes(cairo))]
+pub fn derive_cairo_readable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_readable(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_readable(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let StructAttributes { readable, .. } = parse_struct_attributes(&input.attrs)?;
+    let readable = readable.unwrap_or_else(|| syn::parse_quote!(crate::types::readable));
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "CairoReadable can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "CairoReadable can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut reads = Vec::new();
+    let mut sizes = Vec::new();
+    let mut idents = Vec::new();
+    // Vec fields whose length member is not read yet, with the variable holding their pointer
+    let mut pending_vecs: Vec<(&Ident, Ident)> = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named fields have an ident");
+        let with_len = parse_field_attributes(&field.attrs)?;
+        idents.push(ident);
+
+        if is_vec(&field.ty) {
+            let ptr = format_ident!("{}_ptr", ident);
+            reads.push(quote! {
+                let #ptr = vm.get_relocatable(address)?;
+                let address = (address + 1usize)?;
+            });
+            sizes.push(quote! { 1usize });
+            pending_vecs.push((ident, ptr));
+            if with_len.is_some() {
+                let vecs = pending_vecs.drain(..).map(|(ident, ptr)| {
+                    quote! {
+                        let #ident = #readable::read_array(vm, #ptr, len)?;
+                    }
+                });
+                reads.push(quote! {
+                    let len = #readable::read_len(vm, address)?;
+                    let address = (address + 1usize)?;
+                    #(#vecs)*
+                });
+                sizes.push(quote! { 1usize });
+            }
+        } else if with_len.is_some() {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "#[cairo(with_len)] can only be used on Vec fields",
+            ));
+        } else {
+            let ty = &field.ty;
+            reads.push(quote! {
+                let #ident = <#ty as #readable::CairoReadable>::from_memory(vm, address)?;
+                let address = (address + <#ty as #readable::CairoReadable>::n_fields())?;
+            });
+            sizes.push(quote! { <#ty as #readable::CairoReadable>::n_fields() });
+        }
+    }
+    if let Some((ident, _)) = pending_vecs.first() {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "CairoReadable needs a #[cairo(with_len)] Vec field after this one to read its length",
+        ));
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #readable::CairoReadable for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn from_memory(
+                vm: &::cairo_vm_base::vm::cairo_vm::vm::vm_core::VirtualMachine,
+                address: ::cairo_vm_base::vm::cairo_vm::types::relocatable::Relocatable,
+            ) -> ::core::result::Result<
+                Self,
+                ::cairo_vm_base::vm::cairo_vm::vm::errors::hint_errors::HintError,
+            > {
+                #(#reads)*
+                ::core::result::Result::Ok(Self { #(#idents),* })
+            }
+
+            fn n_fields() -> usize {
+                0usize #(+ #sizes)*
+            }
+        }
+    })
+}
+
+/// Writes the elements to a new segment and stores its pointer.
+fn write_vec(ident: &Ident) -> TokenStream2 {
+    quote! {
+        let segment = vm.add_memory_segment();
+        vm.insert_value(address, segment)?;
+        let mut segment_ptr = segment;
+        for element in &self.#ident {
+            segment_ptr = element.to_memory(vm, segment_ptr)?;
+        }
+        let address = (address + 1usize)?;
+    }
+}
+
+fn write_len(ident: &Ident) -> TokenStream2 {
+    quote! {
+        vm.insert_value(
+            address,
+            ::cairo_vm_base::vm::cairo_vm::Felt252::from(self.#ident.len()),
+        )?;
+        let address = (address + 1usize)?;
+    }
+}
+
+/// Whether `ty` is a `Vec<T>`.
+fn is_vec(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.path.segments.last().is_some_and(|segment| {
+        segment.ident == "Vec"
+            && matches!(
+                &segment.arguments,
+                PathArguments::AngleBracketed(arguments)
+                    if matches!(arguments.args.first(), Some(GenericArgument::Type(_)))
+            )
+    })
+}
+
+/// Attributes of the struct, shared by both derives.
+#[derive(Default)]
+struct StructAttributes {
+    /// Method checking the struct before it is written
+    check: Option<Ident>,
+    /// Path of the module defining `CairoReadable`
+    readable: Option<Path>,
+}
+
+fn parse_struct_attributes(attrs: &[Attribute]) -> syn::Result<StructAttributes> {
+    let mut attributes = StructAttributes::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("cairo")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("check") {
+                let method: LitStr = meta.value()?.parse()?;
+                attributes.check = Some(method.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("readable") {
+                let path: LitStr = meta.value()?.parse()?;
+                attributes.readable = Some(path.parse()?);
+                Ok(())
+            } else {
+                Err(meta
+                    .error("unsupported cairo struct attribute, expected `check` or `readable`"))
+            }
+        })?;
+    }
+    Ok(attributes)
+}
+
+/// Returns `Some` for `with_len` fields, with the name of the length member if given.
+fn parse_field_attributes(attrs: &[Attribute]) -> syn::Result<Option<Option<String>>> {
+    let mut with_len = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("cairo")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("with_len") {
+                let name = if meta.input.peek(syn::Token![=]) {
+                    let name: LitStr = meta.value()?.parse()?;
+                    Some(name.value())
+                } else {
+                    None
+                };
+                with_len = Some(name);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported cairo field attribute, expected `with_len`"))
+            }
+        })?;
+    }
+    Ok(with_len)
+}
@@ -0,0 +1,122 @@
+//! Lists the hints of a compiled program that the hint processor cannot execute.
+//!
+//! An unknown hint only fails the run once it is reached, possibly after most of a long run.
+//! Auditing the program up front reports every such hint before anything is executed.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use cairo_vm_base::vm::cairo_vm::serde::deserialize_program::deserialize_program_json;
+
+use crate::error::Error;
+use crate::hint_processor::CustomHintProcessor;
+
+/// A hint code along with the pcs it is attached to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HintUsage {
+    pub code: String,
+    pub pcs: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HintAudit {
+    /// Every distinct hint code of the program
+    pub hints: Vec<HintUsage>,
+    /// Hints the processor cannot execute
+    pub unhandled: Vec<HintUsage>,
+}
+
+impl HintAudit {
+    /// Fails with the unhandled hint codes, if any.
+    pub fn ensure_handled(&self) -> Result<(), Error> {
+        if self.unhandled.is_empty() {
+            return Ok(());
+        }
+        Err(Error::UnhandledHints(
+            self.unhandled
+                .iter()
+                .map(|usage| usage.code.clone())
+                .collect(),
+        ))
+    }
+}
+
+/// Audits the compiled program at `path` against `processor`.
+pub fn audit_program_file(
+    path: &Path,
+    processor: &CustomHintProcessor,
+) -> Result<HintAudit, Error> {
+    audit_program_json(&std::fs::read(path)?, processor)
+}
+
+/// Audits a compiled program given as JSON against `processor`.
+pub fn audit_program_json(
+    program_json: &[u8],
+    processor: &CustomHintProcessor,
+) -> Result<HintAudit, Error> {
+    let program = deserialize_program_json(program_json)?;
+
+    let mut pcs_by_code: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (pc, hints) in &program.hints {
+        for hint in hints {
+            pcs_by_code.entry(hint.code.clone()).or_default().push(*pc);
+        }
+    }
+
+    let mut audit = HintAudit::default();
+    for (code, pcs) in pcs_by_code {
+        let usage = HintUsage { code, pcs };
+        if !processor.can_execute(&usage.code) {
+            audit.unhandled.push(usage.clone());
+        }
+        audit.hints.push(usage);
+    }
+    Ok(audit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hint_processor::CustomHintProcessorBuilder;
+    use crate::hints::result::HINT_READ_BEACON_MMR_RESULT;
+    use crate::hints::utils::HINT_FELT_DIVMOD;
+    use crate::test_utils::program_json;
+
+    const UNKNOWN_HINT: &str = "print('not a known hint')";
+
+    #[test]
+    fn flags_unknown_hints() {
+        let program = program_json(&[
+            (0, "memory[ap] = segments.add()"),
+            (0, UNKNOWN_HINT),
+            (1, HINT_FELT_DIVMOD),
+            (2, UNKNOWN_HINT),
+        ]);
+
+        let audit = audit_program_json(&program, &CustomHintProcessor::new()).unwrap();
+        assert_eq!(audit.hints.len(), 3);
+        assert_eq!(
+            audit.unhandled,
+            [HintUsage {
+                code: UNKNOWN_HINT.to_string(),
+                pcs: vec![0, 2],
+            }]
+        );
+        assert!(matches!(
+            audit.ensure_handled(),
+            Err(Error::UnhandledHints(codes)) if codes == [UNKNOWN_HINT]
+        ));
+    }
+
+    #[test]
+    fn checks_against_the_given_processor() {
+        let program = program_json(&[(0, HINT_READ_BEACON_MMR_RESULT)]);
+
+        let audit =
+            audit_program_json(&program, &CustomHintProcessorBuilder::empty().build()).unwrap();
+        assert_eq!(audit.unhandled.len(), 1);
+
+        let audit = audit_program_json(&program, &CustomHintProcessor::new()).unwrap();
+        audit.ensure_handled().unwrap();
+    }
+}
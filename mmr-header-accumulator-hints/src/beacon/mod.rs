@@ -0,0 +1,3 @@
+pub mod ssz;
+pub mod update;
+pub mod validate;
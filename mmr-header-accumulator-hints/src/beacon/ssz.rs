@@ -0,0 +1,97 @@
+use alloy_primitives::B256;
+use sha2::{Digest, Sha256};
+
+use crate::types::{uint256_to_b256, BeaconHeaderCairo};
+
+impl BeaconHeaderCairo {
+    /// SSZ hash tree root of the header, i.e. the beacon block root, as `SSZ.hash_header_root` in `ssz.cairo`.
+    /// Slot and proposer index are encoded little-endian and the 5 fields are padded to 8 leaves.
+    pub fn hash_tree_root(&self) -> [u8; 32] {
+        compute_root(&[
+            B256::from(self.slot.0.to_bytes_le()),
+            B256::from(self.proposer_index.0.to_bytes_le()),
+            uint256_to_b256(&self.parent_root),
+            uint256_to_b256(&self.state_root),
+            uint256_to_b256(&self.body_root),
+            B256::ZERO,
+            B256::ZERO,
+            B256::ZERO,
+        ])
+        .0
+    }
+}
+
+/// Computes the header root of `header`. See [`BeaconHeaderCairo::hash_tree_root`].
+pub fn hash_header_root(header: &BeaconHeaderCairo) -> B256 {
+    B256::from(header.hash_tree_root())
+}
+
+/// Computes the SHA-256 merkle root of `leaves`, as `MerkleTree.compute_root` in `ssz.cairo`.
+/// The number of leaves must be a power of 2, which is checked at compile time.
+pub fn compute_root<const N: usize>(leaves: &[B256; N]) -> B256 {
+    const {
+        assert!(
+            N.is_power_of_two(),
+            "the number of leaves must be a power of 2"
+        )
+    };
+
+    let mut nodes = leaves.to_vec();
+    while nodes.len() > 1 {
+        nodes = nodes
+            .chunks(2)
+            .map(|pair| hash_pair(pair[0], pair[1]))
+            .collect();
+    }
+    nodes[0]
+}
+
+/// SHA-256 of the concatenation of two nodes, as `SSZ.hash_pair_container`.
+pub fn hash_pair(left: B256, right: B256) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    B256::from_slice(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::example_input;
+
+    #[test]
+    fn computes_the_root_of_zero_leaves() {
+        // Zero hash of depth 3 of the consensus specs
+        assert_eq!(
+            compute_root(&[B256::ZERO; 8]),
+            "0xc78009fdf07fc56a11f122370658a353aaa542ed63e44c4bc15ff4cd105ab33c"
+                .parse::<B256>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn hashes_mainnet_headers() {
+        let headers = example_input().added_headers;
+
+        // Block roots of mainnet slots 7954432 and 7954463, as printed by `assert_header_linkage`
+        assert_eq!(
+            hash_header_root(&headers[0]),
+            "0xb29e8f1e87745053799b4cb5a222b1912c9d8ed8dab2404a39d1b1b4e87e3cd1"
+                .parse::<B256>()
+                .unwrap()
+        );
+        assert_eq!(
+            hash_header_root(&headers[31]),
+            "0xa78a7ea0aea904bec2dca1541e3d9de038beb47386ef742f4c1d4773f5b132cb"
+                .parse::<B256>()
+                .unwrap()
+        );
+        for pair in headers.windows(2) {
+            assert_eq!(
+                hash_header_root(&pair[0]),
+                uint256_to_b256(&pair[1].parent_root)
+            );
+        }
+    }
+}
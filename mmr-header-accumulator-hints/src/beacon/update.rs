@@ -0,0 +1,153 @@
+use cairo_vm_base::types::{felt::Felt, uint256::Uint256};
+use cairo_vm_base::vm::cairo_vm::Felt252;
+
+use crate::beacon::ssz::hash_header_root;
+use crate::mmr::accumulator::MmrAccumulator;
+use crate::mmr::error::MmrError;
+use crate::mmr::proof::{last_leaf_position, sibling_path};
+use crate::mmr::store::{MemoryNodeStore, NodeStore};
+use crate::mmr::verify::verify_last_leaf;
+use crate::types::{
+    b256_to_uint256, BeaconHeaderCairo, BeaconMmrUpdateCairo, LastLeafProofCairo, MmrSnapshotCairo,
+    MmrStartState,
+};
+
+/// Builds a ready-to-run update appending `headers` to the MMR described by `start_state`.
+pub fn build_beacon_mmr_update(
+    start_state: &MmrStartState,
+    headers: &[BeaconHeaderCairo],
+) -> Result<BeaconMmrUpdateCairo, MmrError> {
+    let store = MemoryNodeStore::from_stored_nodes(&start_state.nodes)?;
+    build_beacon_mmr_update_from_store(
+        &store,
+        &start_state.snapshot,
+        &start_state.last_header_root,
+        headers,
+    )
+}
+
+/// Same as [`build_beacon_mmr_update`], reading the last leaf siblings from any node store.
+/// The last leaf proof is verified against the start snapshot, as the program would.
+pub fn build_beacon_mmr_update_from_store<S: NodeStore>(
+    store: &S,
+    start_snapshot: &MmrSnapshotCairo,
+    last_header_root: &Uint256,
+    headers: &[BeaconHeaderCairo],
+) -> Result<BeaconMmrUpdateCairo, MmrError> {
+    let mut accumulator = MmrAccumulator::from_snapshot(start_snapshot)?;
+    let last_leaf_proof =
+        build_last_leaf_proof(store, accumulator.elements_count(), last_header_root)?;
+    verify_last_leaf(&last_leaf_proof, start_snapshot)?;
+
+    for header in headers {
+        accumulator.append_header_root(hash_header_root(header));
+    }
+
+    Ok(BeaconMmrUpdateCairo {
+        start_snapshot: start_snapshot.clone(),
+        end_snapshot: accumulator.snapshot(),
+        added_headers: headers.to_vec(),
+        last_leaf_proof,
+    })
+}
+
+/// Builds the proof of the last leaf of an MMR of size `mmr_size`, as checked by `verify_last_leaf`.
+pub fn build_last_leaf_proof<S: NodeStore>(
+    store: &S,
+    mmr_size: u128,
+    header_root: &Uint256,
+) -> Result<LastLeafProofCairo, MmrError> {
+    let header_position = last_leaf_position(mmr_size)?;
+    let (poseidon_path, keccak_path) = sibling_path(store, header_position, mmr_size)?;
+
+    Ok(LastLeafProofCairo {
+        header_root: header_root.clone(),
+        header_position: Felt(Felt252::from(header_position)),
+        path_len: Felt(Felt252::from(poseidon_path.len())),
+        poseidon_path: poseidon_path.into_iter().map(Felt).collect(),
+        keccak_path: keccak_path.into_iter().map(b256_to_uint256).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+
+    use super::*;
+    use crate::test_utils::example_input;
+    use crate::types::{uint256_to_b256, StoredMmrNode};
+
+    /// Start state of the example: the genesis MMR holding the leaf of the last leaf proof.
+    fn example_start_state() -> MmrStartState {
+        let input = example_input();
+        let snapshot = input.start_snapshot;
+        let nodes = vec![StoredMmrNode {
+            position: Felt(Felt252::ONE),
+            poseidon: snapshot.poseidon_peaks[0].clone(),
+            keccak: snapshot.keccak_peaks[0].clone(),
+        }];
+        MmrStartState {
+            snapshot,
+            last_header_root: input.last_leaf_proof.header_root,
+            nodes,
+        }
+    }
+
+    fn felts(values: &[Felt]) -> Vec<Felt252> {
+        values.iter().map(|value| value.0).collect()
+    }
+
+    fn b256s(values: &[Uint256]) -> Vec<B256> {
+        values.iter().map(uint256_to_b256).collect()
+    }
+
+    #[test]
+    fn rebuilds_the_example_input() {
+        let expected = example_input();
+        let update =
+            build_beacon_mmr_update(&example_start_state(), &expected.added_headers).unwrap();
+
+        let (end, expected_end) = (&update.end_snapshot, &expected.end_snapshot);
+        assert_eq!(
+            uint256_to_b256(&end.keccak_root),
+            uint256_to_b256(&expected_end.keccak_root)
+        );
+        assert_eq!(end.poseidon_root.0, expected_end.poseidon_root.0);
+        assert_eq!(end.elements_count.0, expected_end.elements_count.0);
+        assert_eq!(b256s(&end.keccak_peaks), b256s(&expected_end.keccak_peaks));
+        assert_eq!(
+            felts(&end.poseidon_peaks),
+            felts(&expected_end.poseidon_peaks)
+        );
+
+        let (proof, expected_proof) = (&update.last_leaf_proof, &expected.last_leaf_proof);
+        assert_eq!(
+            uint256_to_b256(&proof.header_root),
+            uint256_to_b256(&expected_proof.header_root)
+        );
+        assert_eq!(proof.header_position.0, expected_proof.header_position.0);
+        assert_eq!(proof.path_len.0, expected_proof.path_len.0);
+        assert_eq!(
+            felts(&proof.poseidon_path),
+            felts(&expected_proof.poseidon_path)
+        );
+        assert_eq!(
+            b256s(&proof.keccak_path),
+            b256s(&expected_proof.keccak_path)
+        );
+
+        assert_eq!(update.added_headers.len(), 32);
+        assert_eq!(update.validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_a_last_header_root_not_in_the_start_snapshot() {
+        let mut start_state = example_start_state();
+        start_state.last_header_root = b256_to_uint256(B256::ZERO);
+
+        assert!(matches!(
+            build_beacon_mmr_update(&start_state, &example_input().added_headers),
+            Err(MmrError::PoseidonPeakMismatch { position: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+use alloy_primitives::B256;
+use thiserror::Error;
+
+use crate::beacon::ssz::hash_header_root;
+use crate::mmr::accumulator::MmrAccumulator;
+use crate::mmr::error::MmrError;
+use crate::mmr::utils::is_valid_mmr_size;
+use crate::mmr::verify::verify_last_leaf;
+use crate::types::{uint256_to_b256, BeaconMmrUpdateCairo, MmrSnapshotCairo};
+
+/// A check of the Cairo program that the update would fail.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ValidationProblem {
+    #[error("Start snapshot: {0}")]
+    StartSnapshot(MmrError),
+    #[error("End snapshot: {0}")]
+    EndSnapshot(MmrError),
+    #[error("Last leaf proof: {0}")]
+    LastLeafProof(MmrError),
+    #[error("{snapshot} snapshot has {poseidon} Poseidon peaks but {keccak} Keccak peaks")]
+    PeaksLengthMismatch {
+        snapshot: &'static str,
+        poseidon: usize,
+        keccak: usize,
+    },
+    #[error(
+        "Header {index}: parent root {parent_root} does not match the previous root {expected}"
+    )]
+    BrokenLinkage {
+        index: usize,
+        parent_root: B256,
+        expected: B256,
+    },
+}
+
+impl BeaconMmrUpdateCairo {
+    /// Natively performs the checks of `run_beacon_mmr_update` and returns every problem found.
+    ///
+    /// Checks that depend on a valid start snapshot are skipped when it is invalid. As in
+    /// `finalize_mmr`, only the end roots and size are checked, not the end peaks, except for
+    /// both peak lists having the same length, which `write_beacon_input` requires.
+    pub fn validate(&self) -> Result<(), Vec<ValidationProblem>> {
+        let mut problems = Vec::new();
+
+        // write_beacon_input, as both peak lists share a single length member
+        let start_peaks_ok = check_peaks_len("Start", &self.start_snapshot, &mut problems);
+        check_peaks_len("End", &self.end_snapshot, &mut problems);
+
+        // initialize_peaks
+        let accumulator = if start_peaks_ok {
+            MmrAccumulator::from_snapshot(&self.start_snapshot)
+                .map_err(|err| problems.push(ValidationProblem::StartSnapshot(err)))
+                .ok()
+        } else {
+            None
+        };
+
+        // verify_last_leaf
+        if accumulator.is_some() {
+            if let Err(err) = verify_last_leaf(&self.last_leaf_proof, &self.start_snapshot) {
+                problems.push(ValidationProblem::LastLeafProof(err));
+            }
+        }
+
+        // assert_header_linkage, skipping the first check when growing from the genesis MMR
+        let is_genesis = matches!(self.start_snapshot.elements_count(), Ok(1));
+        let mut previous_root = uint256_to_b256(&self.last_leaf_proof.header_root);
+        let mut header_roots = Vec::with_capacity(self.added_headers.len());
+        for (index, header) in self.added_headers.iter().enumerate() {
+            let parent_root = uint256_to_b256(&header.parent_root);
+            if (index != 0 || !is_genesis) && parent_root != previous_root {
+                problems.push(ValidationProblem::BrokenLinkage {
+                    index,
+                    parent_root,
+                    expected: previous_root,
+                });
+            }
+            previous_root = hash_header_root(header);
+            header_roots.push(previous_root);
+        }
+
+        // grow_mmr and finalize_mmr
+        if let Some(mut accumulator) = accumulator {
+            for header_root in header_roots {
+                accumulator.append_header_root(header_root);
+            }
+            problems.extend(
+                self.check_end_snapshot(&accumulator)
+                    .into_iter()
+                    .map(ValidationProblem::EndSnapshot),
+            );
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    fn check_end_snapshot(&self, accumulator: &MmrAccumulator) -> Vec<MmrError> {
+        let mut problems = Vec::new();
+
+        let computed_size = accumulator.elements_count();
+        match self.end_snapshot.elements_count() {
+            Ok(expected) if !is_valid_mmr_size(expected) => {
+                problems.push(MmrError::InvalidSize(expected))
+            }
+            Ok(expected) if expected != computed_size => problems.push(MmrError::SizeMismatch {
+                expected,
+                computed: computed_size,
+            }),
+            Err(err) => problems.push(err),
+            _ => {}
+        }
+
+        let (poseidon_root, keccak_root) = accumulator.roots();
+        if poseidon_root != self.end_snapshot.poseidon_root.0 {
+            problems.push(MmrError::PoseidonRootMismatch {
+                expected: self.end_snapshot.poseidon_root.0,
+                computed: poseidon_root,
+            });
+        }
+        let expected_keccak_root = uint256_to_b256(&self.end_snapshot.keccak_root);
+        if keccak_root != expected_keccak_root {
+            problems.push(MmrError::KeccakRootMismatch {
+                expected: expected_keccak_root,
+                computed: keccak_root,
+            });
+        }
+
+        problems
+    }
+}
+
+/// Reports peak lists of different lengths, returning whether they match.
+fn check_peaks_len(
+    snapshot_name: &'static str,
+    snapshot: &MmrSnapshotCairo,
+    problems: &mut Vec<ValidationProblem>,
+) -> bool {
+    let (poseidon, keccak) = (snapshot.poseidon_peaks.len(), snapshot.keccak_peaks.len());
+    if poseidon != keccak {
+        problems.push(ValidationProblem::PeaksLengthMismatch {
+            snapshot: snapshot_name,
+            poseidon,
+            keccak,
+        });
+    }
+    poseidon == keccak
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::example_input;
+    use crate::types::b256_to_uint256;
+    use cairo_vm_base::types::felt::Felt;
+    use cairo_vm_base::vm::cairo_vm::Felt252;
+
+    #[test]
+    fn accepts_the_example_input() {
+        assert_eq!(example_input().validate(), Ok(()));
+    }
+
+    #[test]
+    fn ignores_the_end_peak_values() {
+        let mut input = example_input();
+        input.end_snapshot.keccak_peaks[0] = b256_to_uint256(B256::ZERO);
+        assert_eq!(input.validate(), Ok(()));
+    }
+
+    #[test]
+    fn reports_peak_lists_of_different_lengths() {
+        let mut input = example_input();
+        input.end_snapshot.poseidon_peaks.pop();
+        assert_eq!(
+            input.validate(),
+            Err(vec![ValidationProblem::PeaksLengthMismatch {
+                snapshot: "End",
+                poseidon: 1,
+                keccak: 2,
+            }])
+        );
+
+        let mut input = example_input();
+        let peak = input.start_snapshot.keccak_peaks[0].clone();
+        input.start_snapshot.keccak_peaks.push(peak);
+        assert_eq!(
+            input.validate(),
+            Err(vec![ValidationProblem::PeaksLengthMismatch {
+                snapshot: "Start",
+                poseidon: 1,
+                keccak: 2,
+            }])
+        );
+    }
+
+    #[test]
+    fn reports_an_end_root_mismatch() {
+        let mut input = example_input();
+        input.end_snapshot.poseidon_root = Felt(Felt252::ONE);
+
+        let problems = input.validate().unwrap_err();
+        assert!(matches!(
+            problems[..],
+            [ValidationProblem::EndSnapshot(
+                MmrError::PoseidonRootMismatch { .. }
+            )]
+        ));
+    }
+
+    #[test]
+    fn reports_a_broken_linkage() {
+        let mut input = example_input();
+        input.added_headers[31].parent_root = b256_to_uint256(B256::ZERO);
+
+        let problems = input.validate().unwrap_err();
+        assert!(matches!(
+            problems[0],
+            ValidationProblem::BrokenLinkage { index: 31, .. }
+        ));
+    }
+}
@@ -0,0 +1,256 @@
+//! Hint codes executed by the builtin hint processor of `cairo-vm`.
+//!
+//! The builtin processor matches the hint code against the constants of `hint_code` and doesn't
+//! expose the list, so it is kept here, in the order of its match. The hints only available with
+//! the `test_utils` feature are left out. Keep in sync when upgrading `cairo-vm`: a test runs every
+//! listed code through the processor and fails on the ones it no longer matches.
+
+use cairo_vm_base::vm::cairo_vm::hint_processor::builtin_hint_processor::hint_code::*;
+
+pub const BUILTIN_HINT_CODES: &[&str] = &[
+    ADD_SEGMENT,
+    IS_NN,
+    IS_NN_OUT_OF_RANGE,
+    ASSERT_LE_FELT,
+    ASSERT_LE_FELT_EXCLUDED_2,
+    ASSERT_LE_FELT_EXCLUDED_1,
+    ASSERT_LE_FELT_EXCLUDED_0,
+    IS_LE_FELT,
+    ASSERT_250_BITS,
+    IS_250_BITS,
+    IS_ADDR_BOUNDED,
+    IS_POSITIVE,
+    SPLIT_INT_ASSERT_RANGE,
+    SPLIT_INT,
+    ASSERT_NOT_EQUAL,
+    ASSERT_NN,
+    SQRT,
+    ASSERT_NOT_ZERO,
+    IS_QUAD_RESIDUE,
+    VM_EXIT_SCOPE,
+    MEMCPY_ENTER_SCOPE,
+    MEMSET_ENTER_SCOPE,
+    MEMCPY_CONTINUE_COPYING,
+    MEMSET_CONTINUE_LOOP,
+    SPLIT_FELT,
+    UNSIGNED_DIV_REM,
+    SIGNED_DIV_REM,
+    ASSERT_LT_FELT,
+    FIND_ELEMENT,
+    SEARCH_SORTED_LOWER,
+    POW,
+    SET_ADD,
+    DICT_NEW,
+    DICT_READ,
+    DICT_WRITE,
+    DEFAULT_DICT_NEW,
+    SQUASH_DICT_INNER_FIRST_ITERATION,
+    USORT_ENTER_SCOPE,
+    USORT_BODY,
+    USORT_VERIFY,
+    USORT_VERIFY_MULTIPLICITY_ASSERT,
+    USORT_VERIFY_MULTIPLICITY_BODY,
+    BLAKE2S_COMPUTE,
+    VERIFY_ZERO_V1,
+    VERIFY_ZERO_V2,
+    VERIFY_ZERO_V3,
+    VERIFY_ZERO_EXTERNAL_SECP,
+    NONDET_BIGINT3_V1,
+    NONDET_BIGINT3_V2,
+    REDUCE_V1,
+    REDUCE_V2,
+    REDUCE_ED25519,
+    BLAKE2S_FINALIZE,
+    BLAKE2S_FINALIZE_V2,
+    BLAKE2S_FINALIZE_V3,
+    BLAKE2S_ADD_UINT256,
+    BLAKE2S_ADD_UINT256_BIGEND,
+    UNSAFE_KECCAK,
+    UNSAFE_KECCAK_FINALIZE,
+    SQUASH_DICT_INNER_SKIP_LOOP,
+    SQUASH_DICT_INNER_CHECK_ACCESS_INDEX,
+    SQUASH_DICT_INNER_CONTINUE_LOOP,
+    SQUASH_DICT_INNER_ASSERT_LEN_KEYS,
+    SQUASH_DICT_INNER_LEN_ASSERT,
+    SQUASH_DICT_INNER_USED_ACCESSES_ASSERT,
+    SQUASH_DICT_INNER_NEXT_KEY,
+    SQUASH_DICT,
+    VM_ENTER_SCOPE,
+    DICT_UPDATE,
+    DICT_SQUASH_COPY_DICT,
+    DICT_SQUASH_UPDATE_PTR,
+    UINT256_ADD,
+    UINT256_ADD_LOW,
+    UINT128_ADD,
+    UINT256_SUB,
+    SPLIT_64,
+    UINT256_SQRT,
+    UINT256_SQRT_FELT,
+    UINT256_SIGNED_NN,
+    UINT256_UNSIGNED_DIV_REM,
+    UINT256_EXPANDED_UNSIGNED_DIV_REM,
+    BIGINT_TO_UINT256,
+    IS_ZERO_PACK_V1,
+    IS_ZERO_PACK_V2,
+    IS_ZERO_NONDET,
+    IS_ZERO_INT,
+    IS_ZERO_PACK_EXTERNAL_SECP_V1,
+    IS_ZERO_PACK_EXTERNAL_SECP_V2,
+    IS_ZERO_PACK_ED25519,
+    IS_ZERO_ASSIGN_SCOPE_VARS,
+    IS_ZERO_ASSIGN_SCOPE_VARS_EXTERNAL_SECP,
+    IS_ZERO_ASSIGN_SCOPE_VARS_ED25519,
+    DIV_MOD_N_PACKED_DIVMOD_V1,
+    GET_FELT_BIT_LENGTH,
+    BIGINT_PACK_DIV_MOD,
+    BIGINT_SAFE_DIV,
+    DIV_MOD_N_PACKED_DIVMOD_EXTERNAL_N,
+    DIV_MOD_N_SAFE_DIV,
+    DIV_MOD_N_SAFE_DIV_PLUS_ONE,
+    GET_POINT_FROM_X,
+    EC_NEGATE,
+    EC_NEGATE_EMBEDDED_SECP,
+    EC_DOUBLE_SLOPE_V1,
+    EC_DOUBLE_SLOPE_V2,
+    EC_DOUBLE_SLOPE_V3,
+    EC_DOUBLE_SLOPE_V4,
+    EC_DOUBLE_SLOPE_EXTERNAL_CONSTS,
+    COMPUTE_SLOPE_V1,
+    SQUARE_SLOPE_X_MOD_P,
+    COMPUTE_SLOPE_V2,
+    COMPUTE_SLOPE_SECP256R1_V1,
+    COMPUTE_SLOPE_SECP256R1_V2,
+    IMPORT_SECP256R1_P,
+    COMPUTE_SLOPE_WHITELIST,
+    EC_DOUBLE_ASSIGN_NEW_X_V1,
+    EC_DOUBLE_ASSIGN_NEW_X_V2,
+    EC_DOUBLE_ASSIGN_NEW_X_V3,
+    EC_DOUBLE_ASSIGN_NEW_X_V4,
+    EC_DOUBLE_ASSIGN_NEW_Y,
+    KECCAK_WRITE_ARGS,
+    COMPARE_BYTES_IN_WORD_NONDET,
+    SHA256_MAIN_CONSTANT_INPUT_LENGTH,
+    SHA256_MAIN_ARBITRARY_INPUT_LENGTH,
+    SHA256_INPUT,
+    SHA256_FINALIZE,
+    CAIRO_KECCAK_INPUT_IS_FULL_WORD,
+    COMPARE_KECCAK_FULL_RATE_IN_BYTES_NONDET,
+    BLOCK_PERMUTATION,
+    BLOCK_PERMUTATION_WHITELIST_V1,
+    BLOCK_PERMUTATION_WHITELIST_V2,
+    CAIRO_KECCAK_FINALIZE_V1,
+    CAIRO_KECCAK_FINALIZE_V2,
+    FAST_EC_ADD_ASSIGN_NEW_X,
+    FAST_EC_ADD_ASSIGN_NEW_X_V2,
+    FAST_EC_ADD_ASSIGN_NEW_X_V3,
+    FAST_EC_ADD_ASSIGN_NEW_Y,
+    EC_MUL_INNER,
+    RELOCATE_SEGMENT,
+    TEMPORARY_ARRAY,
+    VERIFY_ECDSA_SIGNATURE,
+    SPLIT_OUTPUT_0,
+    SPLIT_OUTPUT_1,
+    SPLIT_INPUT_3,
+    SPLIT_INPUT_6,
+    SPLIT_INPUT_9,
+    SPLIT_INPUT_12,
+    SPLIT_INPUT_15,
+    SPLIT_N_BYTES,
+    SPLIT_OUTPUT_MID_LOW_HIGH,
+    NONDET_N_GREATER_THAN_10,
+    NONDET_N_GREATER_THAN_2,
+    NONDET_ELEMENTS_OVER_TEN,
+    NONDET_ELEMENTS_OVER_TWO,
+    RANDOM_EC_POINT,
+    CHAINED_EC_OP_RANDOM_EC_POINT,
+    RECOVER_Y,
+    PACK_MODN_DIV_MODN,
+    XS_SAFE_DIV,
+    UINT384_UNSIGNED_DIV_REM,
+    UINT384_SPLIT_128,
+    ADD_NO_UINT384_CHECK,
+    UINT384_SQRT,
+    UNSIGNED_DIV_REM_UINT768_BY_UINT384,
+    UNSIGNED_DIV_REM_UINT768_BY_UINT384_STRIPPED,
+    SUB_REDUCED_A_AND_REDUCED_B,
+    UINT384_GET_SQUARE_ROOT,
+    UINT256_GET_SQUARE_ROOT,
+    UINT384_SIGNED_NN,
+    UINT384_DIV,
+    UINT256_MUL_DIV_MOD,
+    IMPORT_SECP256R1_ALPHA,
+    IMPORT_SECP256R1_N,
+    UINT512_UNSIGNED_DIV_REM,
+    HI_MAX_BITLEN,
+    QUAD_BIT,
+    INV_MOD_P_UINT256,
+    INV_MOD_P_UINT512,
+    DI_BIT,
+    EXAMPLE_BLAKE2S_COMPRESS,
+    EC_RECOVER_DIV_MOD_N_PACKED,
+    EC_RECOVER_SUB_A_B,
+    A_B_BITAND_1,
+    ASSERT_LE_FELT_V_0_6,
+    ASSERT_LE_FELT_V_0_8,
+    EC_RECOVER_PRODUCT_MOD,
+    EC_RECOVER_PRODUCT_DIV_M,
+    SPLIT_XX,
+    RUN_P_CIRCUIT,
+    RUN_P_CIRCUIT_WITH_LARGE_BATCH_SIZE,
+    EXCESS_BALANCE,
+];
+
+/// Whether the builtin hint processor executes `code`.
+pub fn is_builtin_hint(code: &str) -> bool {
+    BUILTIN_HINT_CODES.contains(&code)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use cairo_vm_base::vm::cairo_vm::{
+        hint_processor::{
+            builtin_hint_processor::builtin_hint_processor_definition::BuiltinHintProcessor,
+            hint_processor_definition::HintProcessorLogic,
+        },
+        serde::deserialize_program::ApTracking,
+        types::exec_scope::ExecutionScopes,
+        vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
+    };
+
+    use super::*;
+
+    /// Runs every listed code without its ids. Known hints fail on their missing variables or
+    /// scopes, while a code the processor doesn't match fails with `UnknownHint`.
+    #[test]
+    fn lists_only_hints_of_the_builtin_processor() {
+        let mut processor = BuiltinHintProcessor::new_empty();
+        let unknown: Vec<&str> = BUILTIN_HINT_CODES
+            .iter()
+            .copied()
+            .filter(|code| {
+                let hint_data = processor
+                    .compile_hint(code, &ApTracking::new(), &HashMap::new(), &[])
+                    .unwrap();
+                let mut vm = VirtualMachine::new(false);
+                vm.add_memory_segment();
+                vm.add_memory_segment();
+                let result = processor.execute_hint(
+                    &mut vm,
+                    &mut ExecutionScopes::new(),
+                    &hint_data,
+                    &HashMap::new(),
+                );
+                matches!(result, Err(HintError::UnknownHint(_)))
+            })
+            .collect();
+        assert!(unknown.is_empty(), "unknown hint codes: {unknown:#?}");
+    }
+
+    #[test]
+    fn rejects_other_hints() {
+        assert!(is_builtin_hint(ADD_SEGMENT));
+        assert!(!is_builtin_hint("write_beacon_input()"));
+    }
+}
@@ -1,13 +1,21 @@
 use cairo_vm_base::vm::cairo_vm::{
+    air_public_input::PublicInputError,
     cairo_run::EncodeTraceError,
+    program_hash::ProgramHashError,
     types::errors::program_errors::ProgramError,
     vm::errors::{
-        cairo_run_errors::CairoRunError, runner_errors::RunnerError, trace_errors::TraceError,
-        vm_errors::VirtualMachineError,
+        cairo_run_errors::CairoRunError, hint_errors::HintError, runner_errors::RunnerError,
+        trace_errors::TraceError, vm_errors::VirtualMachineError, vm_exception::VmException,
     },
 };
+use std::path::PathBuf;
 use thiserror::Error;
 
+use crate::beacon::validate::ValidationProblem;
+use crate::layout::LayoutError;
+use crate::mmr::error::MmrError;
+use crate::output::OutputError;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Failed to interact with the file system")]
@@ -16,16 +24,147 @@ pub enum Error {
     SerdeJson(#[from] serde_json::Error),
     #[error("{0}")]
     Parse(String),
+    #[error("Compiled program not found at {}", .0.display())]
+    ProgramNotFound(PathBuf),
+    #[error("Invalid runner options: {0}")]
+    RunnerOptions(String),
     #[error(transparent)]
     EncodeTrace(#[from] EncodeTraceError),
     #[error(transparent)]
     VirtualMachine(#[from] VirtualMachineError),
     #[error(transparent)]
+    Hint(#[from] HintError),
+    #[error(transparent)]
     Trace(#[from] TraceError),
     #[error(transparent)]
     Program(#[from] ProgramError),
     #[error(transparent)]
+    ProgramHash(#[from] ProgramHashError),
+    #[error(transparent)]
     CairoRun(#[from] CairoRunError),
     #[error(transparent)]
     Runner(#[from] RunnerError),
+    #[error(transparent)]
+    PublicInput(#[from] PublicInputError),
+    #[error(transparent)]
+    Mmr(#[from] MmrError),
+    #[error(transparent)]
+    Output(#[from] OutputError),
+    #[error(transparent)]
+    Layout(#[from] LayoutError),
+    #[error("The program contains hints that cannot be executed:\n{}", .0.join("\n---\n"))]
+    UnhandledHints(Vec<String>),
+    #[error("Invalid input: {}", join_problems(.0))]
+    InvalidInput(Vec<ValidationProblem>),
+    #[error("Invalid MMR size in the start or end snapshot")]
+    InvalidMmrSize(#[source] Box<VmException>),
+    #[error("Start snapshot roots do not match its peaks")]
+    StartRootMismatch(#[source] Box<VmException>),
+    #[error("Last leaf proof does not match the start snapshot")]
+    LastLeafProofMismatch(#[source] Box<VmException>),
+    #[error("Header {header_index} does not link to the previous header")]
+    BrokenLinkage {
+        header_index: usize,
+        #[source]
+        source: Box<VmException>,
+    },
+    #[error("End snapshot roots or size do not match the grown MMR")]
+    EndSnapshotMismatch(#[source] Box<VmException>),
+}
+
+impl Error {
+    /// Process exit code, distinct for each rejected update.
+    ///
+    /// Input rejected by the native validation exits with the code of the check the program
+    /// would have failed.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidInput(problems) => validation_exit_code(problems),
+            Error::InvalidMmrSize(_) => 3,
+            Error::StartRootMismatch(_) => 4,
+            Error::LastLeafProofMismatch(_) => 5,
+            Error::BrokenLinkage { .. } => 6,
+            Error::EndSnapshotMismatch(_) => 7,
+            _ => 1,
+        }
+    }
+}
+
+/// Exit code of the first check of the program failed by the problems.
+fn validation_exit_code(problems: &[ValidationProblem]) -> i32 {
+    // initialize_peaks checks both sizes before any other check
+    let invalid_size = problems.iter().any(|problem| {
+        matches!(
+            problem,
+            ValidationProblem::StartSnapshot(MmrError::InvalidSize(_))
+                | ValidationProblem::EndSnapshot(MmrError::InvalidSize(_))
+        )
+    });
+    if invalid_size {
+        return 3;
+    }
+
+    match problems.first() {
+        Some(ValidationProblem::StartSnapshot(_)) => 4,
+        Some(ValidationProblem::LastLeafProof(_)) => 5,
+        Some(ValidationProblem::BrokenLinkage { .. }) => 6,
+        Some(ValidationProblem::EndSnapshot(_)) => 7,
+        Some(ValidationProblem::PeaksLengthMismatch { .. }) | None => 2,
+    }
+}
+
+fn join_problems(problems: &[ValidationProblem]) -> String {
+    problems
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::example_input;
+    use crate::types::BeaconMmrUpdateCairo;
+    use cairo_vm_base::types::felt::Felt;
+    use cairo_vm_base::vm::cairo_vm::Felt252;
+
+    fn input_exit_code(input: &BeaconMmrUpdateCairo) -> i32 {
+        Error::InvalidInput(input.validate().unwrap_err()).exit_code()
+    }
+
+    #[test]
+    fn maps_validation_problems_to_the_failing_check() {
+        let mut input = example_input();
+        input.end_snapshot.poseidon_peaks.pop();
+        assert_eq!(input_exit_code(&input), 2);
+
+        let mut input = example_input();
+        input.start_snapshot.elements_count = Felt(Felt252::from(2));
+        assert_eq!(input_exit_code(&input), 3);
+
+        let mut input = example_input();
+        input.start_snapshot.poseidon_root = Felt(Felt252::ONE);
+        assert_eq!(input_exit_code(&input), 4);
+
+        let mut input = example_input();
+        input.last_leaf_proof.header_position = Felt(Felt252::from(2));
+        assert_eq!(input_exit_code(&input), 5);
+
+        let mut input = example_input();
+        input.added_headers.swap(1, 2);
+        assert_eq!(input_exit_code(&input), 6);
+
+        let mut input = example_input();
+        input.end_snapshot.poseidon_root = Felt(Felt252::ONE);
+        assert_eq!(input_exit_code(&input), 7);
+    }
+
+    #[test]
+    fn reports_an_invalid_end_size_before_other_problems() {
+        let mut input = example_input();
+        input.added_headers.swap(1, 2);
+        input.end_snapshot.elements_count = Felt(Felt252::from(2));
+        assert_eq!(input_exit_code(&input), 3);
+    }
 }
@@ -0,0 +1,253 @@
+//! Maps failures of the Cairo program back to the checks of the MMR update.
+//!
+//! A failing run only reports the pc of the failing instruction and a traceback of return pcs.
+//! The functions of the program are looked up from its identifiers, and the innermost check on
+//! the call stack gives the domain error.
+
+use cairo_vm_base::vm::cairo_vm::{
+    types::program::Program,
+    vm::errors::{cairo_run_errors::CairoRunError, vm_exception::VmException},
+};
+
+use crate::beacon::validate::ValidationProblem;
+use crate::error::Error;
+use crate::types::BeaconMmrUpdateCairo;
+
+const ASSERT_MMR_SIZE_IS_VALID: &str = "src.mmr.utils.assert_mmr_size_is_valid";
+const INITIALIZE_PEAKS: &str = "src.mmr.lib.initialize_peaks";
+const VERIFY_LAST_LEAF: &str = "src.beacon.lib.verify_last_leaf";
+const ASSERT_HEADER_LINKAGE: &str = "src.beacon.lib.assert_header_linkage";
+const FINALIZE_MMR: &str = "src.mmr.lib.finalize_mmr";
+
+/// Converts a failed run into the error of the check that failed, keeping the raw error otherwise.
+pub fn map_cairo_run_error(
+    program: &Program,
+    input: &BeaconMmrUpdateCairo,
+    error: CairoRunError,
+) -> Error {
+    let CairoRunError::VmException(exception) = error else {
+        return Error::CairoRun(error);
+    };
+
+    let call_stack = call_stack_functions(program, &exception);
+    let on_stack = |name: &str| call_stack.iter().any(|function| function == name);
+    let exception = Box::new(exception);
+
+    // Checks are ordered from the innermost, as assert_mmr_size_is_valid runs in initialize_peaks
+    if on_stack(ASSERT_MMR_SIZE_IS_VALID) {
+        Error::InvalidMmrSize(exception)
+    } else if on_stack(INITIALIZE_PEAKS) {
+        Error::StartRootMismatch(exception)
+    } else if on_stack(VERIFY_LAST_LEAF) {
+        Error::LastLeafProofMismatch(exception)
+    } else if on_stack(ASSERT_HEADER_LINKAGE) {
+        // The traceback is truncated for long chains, so the header is found natively
+        match broken_linkage_index(input) {
+            Some(header_index) => Error::BrokenLinkage {
+                header_index,
+                source: exception,
+            },
+            None => Error::CairoRun(CairoRunError::VmException(*exception)),
+        }
+    } else if on_stack(FINALIZE_MMR) {
+        Error::EndSnapshotMismatch(exception)
+    } else {
+        Error::CairoRun(CairoRunError::VmException(*exception))
+    }
+}
+
+/// Names of the functions containing the failing pc and the return pcs of the traceback.
+fn call_stack_functions(program: &Program, exception: &VmException) -> Vec<String> {
+    let mut functions: Vec<(usize, &str)> = program
+        .iter_identifiers()
+        .filter(|(_, identifier)| identifier.type_.as_deref() == Some("function"))
+        .filter_map(|(name, identifier)| identifier.pc.map(|pc| (pc, name)))
+        .collect();
+    functions.sort_unstable();
+
+    let mut pcs = Vec::new();
+    if exception.pc.segment_index == 0 {
+        pcs.push(exception.pc.offset);
+    }
+    if let Some(traceback) = &exception.traceback {
+        pcs.extend(traceback_pcs(traceback));
+    }
+
+    pcs.into_iter()
+        .filter_map(|pc| {
+            let index = functions.partition_point(|(start, _)| *start <= pc);
+            index
+                .checked_sub(1)
+                .map(|index| functions[index].1.to_string())
+        })
+        .collect()
+}
+
+/// Parses the program segment pcs out of a traceback, formatted as `(pc=0:<offset>)`.
+fn traceback_pcs(traceback: &str) -> Vec<usize> {
+    traceback
+        .split("(pc=0:")
+        .skip(1)
+        .filter_map(|rest| {
+            let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()
+        })
+        .collect()
+}
+
+fn broken_linkage_index(input: &BeaconMmrUpdateCairo) -> Option<usize> {
+    input
+        .validate()
+        .err()?
+        .into_iter()
+        .find_map(|problem| match problem {
+            ValidationProblem::BrokenLinkage { index, .. } => Some(index),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_vm_base::vm::cairo_vm::{
+        types::relocatable::Relocatable, vm::errors::vm_errors::VirtualMachineError,
+    };
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_utils::example_input;
+
+    const MAIN_PC: usize = 0;
+    const INITIALIZE_PEAKS_PC: usize = 10;
+    const ASSERT_MMR_SIZE_IS_VALID_PC: usize = 20;
+    const VERIFY_LAST_LEAF_PC: usize = 30;
+    const ASSERT_HEADER_LINKAGE_PC: usize = 40;
+    const FINALIZE_MMR_PC: usize = 50;
+    const HASH_HEADER_PC: usize = 60;
+
+    /// Program with the functions of the checks laid out at known pcs.
+    fn program() -> Program {
+        let function = |pc: usize| json!({"type": "function", "pc": pc, "decorators": []});
+        let json = json!({
+            "prime": "0x800000000000011000000000000000000000000000000000000000000000001",
+            "builtins": [],
+            "data": ["0x208b7fff7fff7ffe"],
+            "identifiers": {
+                "__main__.main": function(MAIN_PC),
+                INITIALIZE_PEAKS: function(INITIALIZE_PEAKS_PC),
+                ASSERT_MMR_SIZE_IS_VALID: function(ASSERT_MMR_SIZE_IS_VALID_PC),
+                "src.mmr.utils.assert_mmr_size_is_valid.end": {
+                    "type": "label",
+                    "pc": ASSERT_MMR_SIZE_IS_VALID_PC + 5,
+                },
+                VERIFY_LAST_LEAF: function(VERIFY_LAST_LEAF_PC),
+                ASSERT_HEADER_LINKAGE: function(ASSERT_HEADER_LINKAGE_PC),
+                FINALIZE_MMR: function(FINALIZE_MMR_PC),
+                "src.beacon.ssz.hash_header": function(HASH_HEADER_PC),
+                "src.mmr.lib.MAX_PEAKS": {"type": "const", "value": 64},
+            },
+            "hints": {},
+            "reference_manager": {"references": []},
+            "attributes": [],
+            "debug_info": null,
+        });
+        Program::from_bytes(&serde_json::to_vec(&json).unwrap(), Some("main")).unwrap()
+    }
+
+    /// Failure at `pc`, called from the return pcs of `callers`, innermost last.
+    fn vm_exception(pc: usize, callers: &[usize]) -> CairoRunError {
+        let traceback = callers
+            .iter()
+            .map(|caller| format!("Unknown location (pc=0:{caller})\n"))
+            .collect::<String>();
+        CairoRunError::VmException(VmException {
+            pc: Relocatable::from((0, pc)),
+            inst_location: None,
+            inner_exc: VirtualMachineError::NoDst,
+            error_attr_value: None,
+            traceback: (!callers.is_empty())
+                .then(|| format!("Cairo traceback (most recent call last):\n{traceback}")),
+        })
+    }
+
+    fn map_error(input: &BeaconMmrUpdateCairo, error: CairoRunError) -> Error {
+        map_cairo_run_error(&program(), input, error)
+    }
+
+    #[test]
+    fn maps_failures_to_the_innermost_check() {
+        let input = example_input();
+        let error = vm_exception(
+            ASSERT_MMR_SIZE_IS_VALID_PC + 6,
+            &[2, INITIALIZE_PEAKS_PC + 3],
+        );
+        assert!(matches!(map_error(&input, error), Error::InvalidMmrSize(_)));
+
+        let error = vm_exception(INITIALIZE_PEAKS_PC + 8, &[2]);
+        assert!(matches!(
+            map_error(&input, error),
+            Error::StartRootMismatch(_)
+        ));
+
+        // Failing in a helper called by the check
+        let error = vm_exception(HASH_HEADER_PC + 1, &[3, VERIFY_LAST_LEAF_PC + 4]);
+        assert!(matches!(
+            map_error(&input, error),
+            Error::LastLeafProofMismatch(_)
+        ));
+
+        let error = vm_exception(FINALIZE_MMR_PC, &[]);
+        assert!(matches!(
+            map_error(&input, error),
+            Error::EndSnapshotMismatch(_)
+        ));
+    }
+
+    #[test]
+    fn reports_the_broken_header() {
+        let mut input = example_input();
+        input.added_headers.swap(1, 2);
+        let error = vm_exception(
+            ASSERT_HEADER_LINKAGE_PC + 2,
+            &[5, ASSERT_HEADER_LINKAGE_PC + 7],
+        );
+        assert!(matches!(
+            map_error(&input, error),
+            Error::BrokenLinkage {
+                header_index: 1,
+                ..
+            }
+        ));
+
+        // The native checks find no broken link
+        let error = vm_exception(ASSERT_HEADER_LINKAGE_PC + 2, &[5]);
+        assert!(matches!(
+            map_error(&example_input(), error),
+            Error::CairoRun(CairoRunError::VmException(_))
+        ));
+    }
+
+    #[test]
+    fn keeps_other_failures() {
+        let input = example_input();
+        let error = vm_exception(HASH_HEADER_PC + 1, &[MAIN_PC + 4]);
+        assert!(matches!(
+            map_error(&input, error),
+            Error::CairoRun(CairoRunError::VmException(_))
+        ));
+
+        let error = CairoRunError::VirtualMachine(VirtualMachineError::NoDst);
+        assert!(matches!(
+            map_error(&input, error),
+            Error::CairoRun(CairoRunError::VirtualMachine(_))
+        ));
+    }
+
+    #[test]
+    fn parses_traceback_pcs() {
+        let traceback = "Cairo traceback (most recent call last):\n\
+            src/main.cairo:12:5: (pc=0:7)\n\
+            Unknown location (pc=1:3)\n\
+            Unknown location (pc=0:42)\n";
+        assert_eq!(traceback_pcs(traceback), vec![7, 42]);
+    }
+}
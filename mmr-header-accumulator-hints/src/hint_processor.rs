@@ -1,10 +1,5 @@
-use crate::hints::{
-    input::{write_beacon_input, HINT_WRITE_BEACON_INPUT},
-    mmr::{
-        hint_is_position_in_mmr_array, mmr_bit_length, mmr_left_child,
-        HINT_IS_POSITION_IN_MMR_ARRAY, MMR_BIT_LENGTH, MMR_LEFT_CHILD,
-    },
-};
+use crate::builtin_hints::is_builtin_hint;
+use crate::hints::{get_hints, input::HINT_WRITE_BEACON_INPUT};
 use cairo_vm_base::default_hints::{default_hint_mapping, HintImpl};
 use cairo_vm_base::vm::cairo_vm::{
     hint_processor::{
@@ -22,12 +17,23 @@ use cairo_vm_base::vm::cairo_vm::{
 };
 use std::any::Any;
 use std::collections::HashMap;
+use std::fmt;
 
 pub struct CustomHintProcessor {
     hints: HashMap<String, HintImpl>,
     builtin_hint_proc: BuiltinHintProcessor,
 }
 
+impl fmt::Debug for CustomHintProcessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut hints: Vec<&String> = self.hints.keys().collect();
+        hints.sort();
+        f.debug_struct("CustomHintProcessor")
+            .field("hints", &hints)
+            .finish_non_exhaustive()
+    }
+}
+
 impl Default for CustomHintProcessor {
     fn default() -> Self {
         Self::new()
@@ -35,22 +41,65 @@ impl Default for CustomHintProcessor {
 }
 
 impl CustomHintProcessor {
+    /// Processor with the default hint mapping and the hints of [`get_hints`].
     pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    pub fn builder() -> CustomHintProcessorBuilder {
+        CustomHintProcessorBuilder::default()
+    }
+
+    /// Whether `code` is one of the registered hints, or a hint of the builtin processor.
+    pub fn can_execute(&self, code: &str) -> bool {
+        self.hints.contains_key(code) || is_builtin_hint(code)
+    }
+}
+
+/// Composes the hints of a `CustomHintProcessor`. Starts from the default hint mapping and the
+/// hints of this crate, a hint registered with an existing code replaces it.
+#[derive(Clone)]
+pub struct CustomHintProcessorBuilder {
+    hints: HashMap<String, HintImpl>,
+}
+
+impl Default for CustomHintProcessorBuilder {
+    fn default() -> Self {
+        let mut hints = default_hint_mapping();
+        hints.extend(get_hints());
+        Self { hints }
+    }
+}
+
+impl CustomHintProcessorBuilder {
+    /// Builder without any hint, only the builtin processor remains as a fallback.
+    pub fn empty() -> Self {
         Self {
-            hints: Self::hints(),
-            builtin_hint_proc: BuiltinHintProcessor::new_empty(),
+            hints: HashMap::new(),
         }
     }
 
-    fn hints() -> HashMap<String, HintImpl> {
-        let mut hints = default_hint_mapping();
-        hints.insert(MMR_BIT_LENGTH.to_string(), mmr_bit_length);
-        hints.insert(MMR_LEFT_CHILD.to_string(), mmr_left_child);
-        hints.insert(
-            HINT_IS_POSITION_IN_MMR_ARRAY.to_string(),
-            hint_is_position_in_mmr_array,
-        );
-        hints
+    pub fn with_hint(mut self, code: impl Into<String>, hint: HintImpl) -> Self {
+        self.hints.insert(code.into(), hint);
+        self
+    }
+
+    pub fn with_hints(mut self, hints: impl IntoIterator<Item = (String, HintImpl)>) -> Self {
+        self.hints.extend(hints);
+        self
+    }
+
+    /// Replaces the writer of `write_beacon_input()`, for programs reading their input from
+    /// another scope variable or source.
+    pub fn with_input_writer(self, writer: HintImpl) -> Self {
+        self.with_hint(HINT_WRITE_BEACON_INPUT, writer)
+    }
+
+    pub fn build(self) -> CustomHintProcessor {
+        CustomHintProcessor {
+            hints: self.hints,
+            builtin_hint_proc: BuiltinHintProcessor::new_empty(),
+        }
     }
 }
 
@@ -76,18 +125,7 @@ impl HintProcessorLogic for CustomHintProcessor {
         if let Some(hpd) = hint_data.downcast_ref::<HintProcessorData>() {
             let hint_code = hpd.code.as_str();
 
-            let res = match hint_code {
-                HINT_WRITE_BEACON_INPUT => write_beacon_input(vm, exec_scopes, hpd, constants),
-                _ => Err(HintError::UnknownHint(
-                    hint_code.to_string().into_boxed_str(),
-                )),
-            };
-
-            if !matches!(res, Err(HintError::UnknownHint(_))) {
-                return res.map(|_| HintExtension::default());
-            }
-
-            // First try our custom hints
+            // First try the registered hints
             if let Some(hint_impl) = self.hints.get(hint_code) {
                 return hint_impl(vm, exec_scopes, hpd, constants)
                     .map(|_| HintExtension::default());
@@ -0,0 +1,262 @@
+//! Native versions of the print hints of `src/debug/lib.cairo`.
+//!
+//! Instead of going to stdout, each print is recorded as a [`DebugPrint`] in the log held by the
+//! main execution scope, which the runner returns along with the outcome. Without a log, such as
+//! under another runner, the print is dropped.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use cairo_vm_base::types::uint256::Uint256;
+use cairo_vm_base::vm::cairo_vm::{
+    hint_processor::builtin_hint_processor::{
+        builtin_hint_processor_definition::HintProcessorData,
+        hint_utils::{get_integer_from_var_name, get_relocatable_from_var_name},
+    },
+    types::exec_scope::ExecutionScopes,
+    vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
+    Felt252,
+};
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+
+use crate::types::readable::CairoReadable;
+
+/// Scope variable holding the `Vec<DebugPrint>` log
+pub const DEBUG_LOG: &str = "debug_log";
+/// Scope variable set to `true` to skip the print hints
+pub const DEBUG_SILENCED: &str = "debug_silenced";
+
+pub const HINT_PRINT_FELT: &str = r#"print(f"{ids.value}")"#;
+pub const HINT_PRINT_FELT_HEX: &str = r#"print(f"{hex(ids.value)}")"#;
+pub const HINT_PRINT_STRING: &str = r#"print(f"String: {ids.value}")"#;
+pub const HINT_PRINT_UINT256: &str =
+    r#"print(f"{hex(ids.value.high * 2 ** 128 + ids.value.low)}")"#;
+pub const HINT_PRINT_UINT384: &str = r#"print(f"{hex(ids.value.d3 * 2 ** 144 + ids.value.d2 * 2 ** 96 + ids.value.d1 * 2 ** 48 + ids.value.d0)}")"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugValueKind {
+    Felt,
+    FeltHex,
+    String,
+    Uint256,
+    Uint384,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugPrint {
+    pub kind: DebugValueKind,
+    /// Decimal for felts, the decoded short string for strings, hexadecimal otherwise
+    pub value: String,
+    /// Offset of the hint pc in the program segment
+    pub pc: usize,
+}
+
+impl fmt::Display for DebugPrint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            DebugValueKind::String => write!(f, "String: {}", self.value),
+            _ => write!(f, "{}", self.value),
+        }
+    }
+}
+
+pub fn print_felt(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    hint_data: &HintProcessorData,
+    _constants: &HashMap<String, Felt252>,
+) -> Result<(), HintError> {
+    if is_silenced(exec_scopes) {
+        return Ok(());
+    }
+    let value =
+        get_integer_from_var_name("value", vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
+    record(vm, exec_scopes, DebugValueKind::Felt, value.to_string());
+    Ok(())
+}
+
+pub fn print_felt_hex(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    hint_data: &HintProcessorData,
+    _constants: &HashMap<String, Felt252>,
+) -> Result<(), HintError> {
+    if is_silenced(exec_scopes) {
+        return Ok(());
+    }
+    let value =
+        get_integer_from_var_name("value", vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
+    record(
+        vm,
+        exec_scopes,
+        DebugValueKind::FeltHex,
+        to_hex(&value.to_biguint()),
+    );
+    Ok(())
+}
+
+pub fn print_string(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    hint_data: &HintProcessorData,
+    _constants: &HashMap<String, Felt252>,
+) -> Result<(), HintError> {
+    if is_silenced(exec_scopes) {
+        return Ok(());
+    }
+    let value =
+        get_integer_from_var_name("value", vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
+    record(
+        vm,
+        exec_scopes,
+        DebugValueKind::String,
+        decode_short_string(&value),
+    );
+    Ok(())
+}
+
+pub fn print_uint256(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    hint_data: &HintProcessorData,
+    _constants: &HashMap<String, Felt252>,
+) -> Result<(), HintError> {
+    if is_silenced(exec_scopes) {
+        return Ok(());
+    }
+    let value_ptr =
+        get_relocatable_from_var_name("value", vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
+    let value = Uint256::from_memory(vm, value_ptr)?;
+    record(vm, exec_scopes, DebugValueKind::Uint256, to_hex(&value.0));
+    Ok(())
+}
+
+pub fn print_uint384(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    hint_data: &HintProcessorData,
+    _constants: &HashMap<String, Felt252>,
+) -> Result<(), HintError> {
+    if is_silenced(exec_scopes) {
+        return Ok(());
+    }
+    let value_ptr =
+        get_relocatable_from_var_name("value", vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
+    // Same shifts as the Python hint, d0 to d3 in memory order
+    let value = vm
+        .get_integer_range(value_ptr, 4)?
+        .iter()
+        .zip([0u32, 48, 96, 144])
+        .fold(BigUint::default(), |acc, (limb, shift)| {
+            acc + (limb.to_biguint() << shift)
+        });
+    record(vm, exec_scopes, DebugValueKind::Uint384, to_hex(&value));
+    Ok(())
+}
+
+fn is_silenced(exec_scopes: &ExecutionScopes) -> bool {
+    exec_scopes
+        .data
+        .first()
+        .and_then(|scope| scope.get(DEBUG_SILENCED))
+        .and_then(|silenced| silenced.downcast_ref::<bool>())
+        .copied()
+        .unwrap_or(false)
+}
+
+/// Appends the print to the log of the main scope, as inner scopes are dropped when exited. The
+/// print is dropped if there is no log.
+fn record(
+    vm: &VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    kind: DebugValueKind,
+    value: String,
+) {
+    let entry = DebugPrint {
+        kind,
+        value,
+        pc: vm.get_pc().offset,
+    };
+    if let Some(log) = exec_scopes
+        .data
+        .first_mut()
+        .and_then(|scope| scope.get_mut(DEBUG_LOG))
+        .and_then(|log| log.downcast_mut::<Vec<DebugPrint>>())
+    {
+        log.push(entry);
+    }
+}
+
+fn to_hex(value: &BigUint) -> String {
+    format!("{value:#x}")
+}
+
+/// Decodes a Cairo short string, falling back to the decimal value if it isn't printable ASCII.
+fn decode_short_string(value: &Felt252) -> String {
+    let bytes = value.to_bytes_be();
+    let start = bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(bytes.len());
+    let bytes = &bytes[start..];
+    if !bytes.is_empty()
+        && bytes
+            .iter()
+            .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+    {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::vm_with_ids;
+
+    fn run_print_felt(exec_scopes: &mut ExecutionScopes) {
+        let (mut vm, ids_data) = vm_with_ids(&[("value", Felt252::from(42).into())]);
+        let hint_data = HintProcessorData::new_default(HINT_PRINT_FELT.to_string(), ids_data);
+        print_felt(&mut vm, exec_scopes, &hint_data, &HashMap::new()).unwrap();
+    }
+
+    #[test]
+    fn records_prints_in_the_main_scope_log() {
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value(DEBUG_LOG, Vec::<DebugPrint>::new());
+        exec_scopes.enter_scope(HashMap::new());
+        run_print_felt(&mut exec_scopes);
+        exec_scopes.exit_scope().unwrap();
+
+        assert_eq!(
+            exec_scopes.get::<Vec<DebugPrint>>(DEBUG_LOG).unwrap(),
+            [DebugPrint {
+                kind: DebugValueKind::Felt,
+                value: "42".to_string(),
+                pc: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn drops_prints_without_a_log() {
+        let mut exec_scopes = ExecutionScopes::new();
+        run_print_felt(&mut exec_scopes);
+        assert!(exec_scopes.get::<Vec<DebugPrint>>(DEBUG_LOG).is_err());
+    }
+
+    #[test]
+    fn skips_prints_when_silenced() {
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value(DEBUG_LOG, Vec::<DebugPrint>::new());
+        exec_scopes.insert_value(DEBUG_SILENCED, true);
+        run_print_felt(&mut exec_scopes);
+        assert!(exec_scopes
+            .get::<Vec<DebugPrint>>(DEBUG_LOG)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,28 @@
+use cairo_vm_base::vm::cairo_vm::{vm::errors::hint_errors::HintError, Felt252};
+use thiserror::Error;
+
+/// Failures while writing the program input to memory.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum InputError {
+    #[error("Missing input `{0}` in the execution scopes")]
+    MissingInput(&'static str),
+    #[error("{type_name} has a path_len of {path_len}, but {poseidon} Poseidon and {keccak} Keccak path elements")]
+    PathLengthMismatch {
+        type_name: &'static str,
+        path_len: Felt252,
+        poseidon: usize,
+        keccak: usize,
+    },
+    #[error("{type_name} has {poseidon} Poseidon peaks but {keccak} Keccak peaks")]
+    PeaksLengthMismatch {
+        type_name: &'static str,
+        poseidon: usize,
+        keccak: usize,
+    },
+}
+
+impl From<InputError> for HintError {
+    fn from(error: InputError) -> Self {
+        HintError::CustomHint(error.to_string().into_boxed_str())
+    }
+}
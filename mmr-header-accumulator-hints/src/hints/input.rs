@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use cairo_vm_base::cairo_type::{CairoType, CairoWritable};
+use cairo_vm_base::cairo_type::CairoWritable;
 use cairo_vm_base::vm::cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::HintProcessorData;
 use cairo_vm_base::vm::cairo_vm::hint_processor::builtin_hint_processor::hint_utils::{get_ptr_from_var_name, get_relocatable_from_var_name};
 use cairo_vm_base::vm::cairo_vm::types::exec_scope::ExecutionScopes;
@@ -8,7 +8,8 @@ use cairo_vm_base::vm::cairo_vm::vm::errors::hint_errors::HintError;
 use cairo_vm_base::vm::cairo_vm::vm::vm_core::VirtualMachine;
 use cairo_vm_base::vm::cairo_vm::Felt252;
 
-use crate::types::{BeaconHeaderCairo, BeaconMmrUpdateCairo, LastLeafProofCairo, MmrSnapshotCairo};
+use crate::hints::error::InputError;
+use crate::types::{BeaconMmrUpdateCairo, LastLeafProofCairo, MmrSnapshotCairo};
 
 pub const HINT_WRITE_BEACON_INPUT: &str = "write_beacon_input()";
 
@@ -20,7 +21,7 @@ pub fn write_beacon_input(
 ) -> Result<(), HintError> {
     let beacon_mmr_update: BeaconMmrUpdateCairo = exec_scopes
         .get::<BeaconMmrUpdateCairo>("beacon_mmr_update")
-        .unwrap();
+        .map_err(|_| InputError::MissingInput("beacon_mmr_update"))?;
     let start_mmr_snapshot_ptr = get_relocatable_from_var_name(
         "start_mmr_snapshot",
         vm,
@@ -74,115 +75,168 @@ pub fn write_beacon_input(
     Ok(())
 }
 
-impl CairoWritable for MmrSnapshotCairo {
-    fn to_memory(
-        &self,
-        vm: &mut cairo_vm_base::vm::cairo_vm::vm::vm_core::VirtualMachine,
-        address: cairo_vm_base::vm::cairo_vm::types::relocatable::Relocatable,
-    ) -> Result<
-        cairo_vm_base::vm::cairo_vm::types::relocatable::Relocatable,
-        cairo_vm_base::vm::cairo_vm::vm::errors::hint_errors::HintError,
-    > {
-        let address_start = address;
-        let address = self.keccak_root.to_memory(vm, address)?;
-        let address = self.poseidon_root.to_memory(vm, address)?;
-        let address = self.elements_count.to_memory(vm, address)?;
-
-        // Create segment for keccak peaks and store its pointer
-        let keccak_peaks_segment = vm.add_memory_segment();
-        vm.insert_value(address, keccak_peaks_segment)?;
-        let mut segment_ptr = keccak_peaks_segment;
-        for peak in &self.keccak_peaks {
-            segment_ptr = peak.to_memory(vm, segment_ptr)?;
+impl MmrSnapshotCairo {
+    /// Both peak lists share the `peaks_len` member of the Cairo struct.
+    pub(crate) fn check_peaks_len(&self) -> Result<(), InputError> {
+        if self.poseidon_peaks.len() != self.keccak_peaks.len() {
+            return Err(InputError::PeaksLengthMismatch {
+                type_name: "MmrSnapshotCairo",
+                poseidon: self.poseidon_peaks.len(),
+                keccak: self.keccak_peaks.len(),
+            });
         }
-        let address = (address + 1)?;
-
-        // Create segment for poseidon peaks and store its pointer
-        let poseidon_peaks_segment = vm.add_memory_segment();
-        vm.insert_value(address, poseidon_peaks_segment)?;
-        let mut segment_ptr = poseidon_peaks_segment;
-        for peak in &self.poseidon_peaks {
-            segment_ptr = peak.to_memory(vm, segment_ptr)?;
+        Ok(())
+    }
+}
+
+impl LastLeafProofCairo {
+    /// Both paths are read with the `path_len` member of the Cairo struct.
+    pub(crate) fn check_path_len(&self) -> Result<(), InputError> {
+        if self.path_len.0 != Felt252::from(self.poseidon_path.len())
+            || self.path_len.0 != Felt252::from(self.keccak_path.len())
+        {
+            return Err(InputError::PathLengthMismatch {
+                type_name: "LastLeafProofCairo",
+                path_len: self.path_len.0,
+                poseidon: self.poseidon_path.len(),
+                keccak: self.keccak_path.len(),
+            });
         }
-        let address = (address + 1)?;
+        Ok(())
+    }
+}
 
-        vm.insert_value(address, Felt252::from(self.poseidon_peaks.len()))?;
-        let address = (address + 1)?;
+#[cfg(test)]
+mod tests {
+    use cairo_vm_base::types::felt::Felt;
+    use cairo_vm_base::vm::cairo_vm::{
+        hint_processor::hint_processor_definition::HintReference, types::relocatable::Relocatable,
+    };
 
-        assert!(address == (address_start + Self::n_fields())?);
+    use super::*;
+    use crate::test_utils::{example_input, vm_with_ids};
+    use crate::types::readable::CairoReadable;
 
-        Ok(address)
+    fn struct_size(members: Vec<(&str, usize)>) -> usize {
+        members.iter().map(|(_, size)| size).sum()
     }
 
-    fn n_fields() -> usize {
-        7
-    }
-}
+    /// Runs the hint with the structs laid out one after the other in the execution segment,
+    /// followed by the headers pointer and `n_headers`.
+    fn run_write_beacon_input(
+        input: Option<BeaconMmrUpdateCairo>,
+    ) -> Result<VirtualMachine, HintError> {
+        let snapshot_size = struct_size(MmrSnapshotCairo::cairo_members());
+        let proof_offset = 2 * snapshot_size;
+        let headers_offset = proof_offset + struct_size(LastLeafProofCairo::cairo_members());
+
+        let (mut vm, mut ids_data) = vm_with_ids(&[]);
+        let headers = vm.add_memory_segment();
+        vm.insert_value(Relocatable::from((1, headers_offset)), headers)
+            .unwrap();
+        for (name, offset) in [
+            ("start_mmr_snapshot", 0),
+            ("end_mmr_snapshot", snapshot_size),
+            ("last_leaf_proof", proof_offset),
+            ("headers", headers_offset),
+            ("n_headers", headers_offset + 1),
+        ] {
+            ids_data.insert(name.to_string(), HintReference::new_simple(offset as i32));
+        }
 
-impl CairoWritable for LastLeafProofCairo {
-    fn to_memory(
-        &self,
-        vm: &mut cairo_vm_base::vm::cairo_vm::vm::vm_core::VirtualMachine,
-        address: cairo_vm_base::vm::cairo_vm::types::relocatable::Relocatable,
-    ) -> Result<
-        cairo_vm_base::vm::cairo_vm::types::relocatable::Relocatable,
-        cairo_vm_base::vm::cairo_vm::vm::errors::hint_errors::HintError,
-    > {
-        let address_start = address;
-        let address = self.header_root.to_memory(vm, address)?;
-        let address = self.header_position.to_memory(vm, address)?;
-        let address = self.path_len.to_memory(vm, address)?;
-
-        // Create segment for poseidon path and store its pointer
-        let poseidon_path_segment = vm.add_memory_segment();
-        vm.insert_value(address, poseidon_path_segment)?;
-        let mut segment_ptr = poseidon_path_segment;
-        for path in &self.poseidon_path {
-            segment_ptr = path.to_memory(vm, segment_ptr)?;
+        let mut exec_scopes = ExecutionScopes::new();
+        if let Some(input) = input {
+            exec_scopes.insert_value("beacon_mmr_update", input);
         }
-        let address = (address + 1)?;
-
-        // Create segment for keccak path and store its pointer
-        let keccak_path_segment = vm.add_memory_segment();
-        vm.insert_value(address, keccak_path_segment)?;
-        let mut segment_ptr = keccak_path_segment;
-        for path in &self.keccak_path {
-            segment_ptr = path.to_memory(vm, segment_ptr)?;
+        let hint_data =
+            HintProcessorData::new_default(HINT_WRITE_BEACON_INPUT.to_string(), ids_data);
+        write_beacon_input(&mut vm, &mut exec_scopes, &hint_data, &HashMap::new())?;
+        Ok(vm)
+    }
+
+    fn assert_input_error(result: Result<VirtualMachine, HintError>, expected: InputError) {
+        match result {
+            Err(HintError::CustomHint(message)) => assert_eq!(*message, expected.to_string()),
+            Err(err) => panic!("expected {expected}, got {err}"),
+            Ok(_) => panic!("expected {expected}, but the hint succeeded"),
         }
-        let address = (address + 1)?;
+    }
 
-        assert!(address == (address_start + Self::n_fields())?);
+    #[test]
+    fn writes_the_example_input() {
+        let input = example_input();
+        let vm = run_write_beacon_input(Some(input.clone())).unwrap();
+
+        let snapshot_size = struct_size(MmrSnapshotCairo::cairo_members());
+        for (snapshot, offset) in [
+            (&input.start_snapshot, 0),
+            (&input.end_snapshot, snapshot_size),
+        ] {
+            let written =
+                MmrSnapshotCairo::from_memory(&vm, Relocatable::from((1, offset))).unwrap();
+            assert_eq!(
+                serde_json::to_value(written).unwrap(),
+                serde_json::to_value(snapshot).unwrap()
+            );
+        }
 
-        Ok(address)
+        let n_headers_offset =
+            2 * snapshot_size + struct_size(LastLeafProofCairo::cairo_members()) + 1;
+        assert_eq!(
+            vm.get_integer(Relocatable::from((1, n_headers_offset)))
+                .unwrap()
+                .into_owned(),
+            Felt252::from(input.added_headers.len())
+        );
     }
 
-    fn n_fields() -> usize {
-        6
+    #[test]
+    fn rejects_a_missing_input() {
+        assert_input_error(
+            run_write_beacon_input(None),
+            InputError::MissingInput("beacon_mmr_update"),
+        );
     }
-}
 
-impl CairoWritable for BeaconHeaderCairo {
-    fn to_memory(
-        &self,
-        vm: &mut cairo_vm_base::vm::cairo_vm::vm::vm_core::VirtualMachine,
-        address: cairo_vm_base::vm::cairo_vm::types::relocatable::Relocatable,
-    ) -> Result<
-        cairo_vm_base::vm::cairo_vm::types::relocatable::Relocatable,
-        cairo_vm_base::vm::cairo_vm::vm::errors::hint_errors::HintError,
-    > {
-        let address_start = address;
-        let address = self.slot.to_memory(vm, address)?;
-        let address = self.proposer_index.to_memory(vm, address)?;
-        let address = self.parent_root.to_memory(vm, address)?;
-        let address = self.state_root.to_memory(vm, address)?;
-        let address = self.body_root.to_memory(vm, address)?;
-
-        assert!(address == (address_start + Self::n_fields())?);
-
-        Ok(address)
+    #[test]
+    fn rejects_a_path_len_mismatch() {
+        let mut input = example_input();
+        input.last_leaf_proof.path_len = Felt(Felt252::ONE);
+        assert_input_error(
+            run_write_beacon_input(Some(input)),
+            InputError::PathLengthMismatch {
+                type_name: "LastLeafProofCairo",
+                path_len: Felt252::ONE,
+                poseidon: 0,
+                keccak: 0,
+            },
+        );
+
+        let mut input = example_input();
+        input.last_leaf_proof.poseidon_path.push(Felt(Felt252::ONE));
+        assert_input_error(
+            run_write_beacon_input(Some(input)),
+            InputError::PathLengthMismatch {
+                type_name: "LastLeafProofCairo",
+                path_len: Felt252::ZERO,
+                poseidon: 1,
+                keccak: 0,
+            },
+        );
     }
 
-    fn n_fields() -> usize {
-        8
+    #[test]
+    fn rejects_a_peaks_length_mismatch() {
+        let mut input = example_input();
+        let peak = input.end_snapshot.keccak_peaks[0].clone();
+        input.end_snapshot.keccak_peaks.push(peak);
+        assert_input_error(
+            run_write_beacon_input(Some(input)),
+            InputError::PeaksLengthMismatch {
+                type_name: "MmrSnapshotCairo",
+                poseidon: 2,
+                keccak: 3,
+            },
+        );
     }
 }
@@ -2,21 +2,53 @@ use std::collections::HashMap;
 
 use cairo_vm_base::default_hints::HintImpl;
 
+use crate::hints::debug::{
+    print_felt, print_felt_hex, print_string, print_uint256, print_uint384, HINT_PRINT_FELT,
+    HINT_PRINT_FELT_HEX, HINT_PRINT_STRING, HINT_PRINT_UINT256, HINT_PRINT_UINT384,
+};
+use crate::hints::input::{write_beacon_input, HINT_WRITE_BEACON_INPUT};
 use crate::hints::mmr::{
     hint_is_position_in_mmr_array, mmr_bit_length, mmr_left_child, HINT_IS_POSITION_IN_MMR_ARRAY,
     MMR_BIT_LENGTH, MMR_LEFT_CHILD,
 };
+use crate::hints::result::{read_beacon_mmr_result, HINT_READ_BEACON_MMR_RESULT};
+use crate::hints::sha::{hint_sha256_chunk, HINT_SHA256_CHUNK};
+use crate::hints::utils::{
+    hint_felt_divmod, hint_get_felt_bitlength, HINT_FELT_DIVMOD, HINT_GET_FELT_BITLENGTH,
+};
 
+pub mod debug;
+pub mod error;
 pub mod input;
 pub mod mmr;
+pub mod result;
+pub mod sha;
+pub mod utils;
 
+/// Hints of the accumulator program, keyed by their code. This is the single registry used by
+/// `CustomHintProcessor`, on top of the default hint mapping.
 pub fn get_hints() -> HashMap<String, HintImpl> {
     let mut hints = HashMap::<String, HintImpl>::new();
+    hints.insert(HINT_WRITE_BEACON_INPUT.to_string(), write_beacon_input);
+    hints.insert(
+        HINT_READ_BEACON_MMR_RESULT.to_string(),
+        read_beacon_mmr_result,
+    );
     hints.insert(MMR_BIT_LENGTH.to_string(), mmr_bit_length);
     hints.insert(MMR_LEFT_CHILD.to_string(), mmr_left_child);
     hints.insert(
         HINT_IS_POSITION_IN_MMR_ARRAY.to_string(),
         hint_is_position_in_mmr_array,
     );
+    // Native versions of the Python hints of src/core
+    hints.insert(HINT_SHA256_CHUNK.to_string(), hint_sha256_chunk);
+    hints.insert(HINT_FELT_DIVMOD.to_string(), hint_felt_divmod);
+    hints.insert(HINT_GET_FELT_BITLENGTH.to_string(), hint_get_felt_bitlength);
+    // Print hints of src/debug, recorded in the debug log
+    hints.insert(HINT_PRINT_FELT.to_string(), print_felt);
+    hints.insert(HINT_PRINT_FELT_HEX.to_string(), print_felt_hex);
+    hints.insert(HINT_PRINT_STRING.to_string(), print_string);
+    hints.insert(HINT_PRINT_UINT256.to_string(), print_uint256);
+    hints.insert(HINT_PRINT_UINT384.to_string(), print_uint384);
     hints
 }
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use cairo_vm_base::vm::cairo_vm::hint_processor::builtin_hint_processor::builtin_hint_processor_definition::HintProcessorData;
+use cairo_vm_base::vm::cairo_vm::hint_processor::builtin_hint_processor::hint_utils::get_relocatable_from_var_name;
+use cairo_vm_base::vm::cairo_vm::types::exec_scope::ExecutionScopes;
+use cairo_vm_base::vm::cairo_vm::vm::errors::exec_scope_errors::ExecScopeError;
+use cairo_vm_base::vm::cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm_base::vm::cairo_vm::vm::vm_core::VirtualMachine;
+use cairo_vm_base::vm::cairo_vm::Felt252;
+
+use crate::types::readable::CairoReadable;
+use crate::types::MmrSnapshotCairo;
+
+pub const HINT_READ_BEACON_MMR_RESULT: &str = "read_beacon_mmr_result()";
+
+/// Scope variable holding the end snapshot computed by the program
+pub const BEACON_MMR_RESULT: &str = "beacon_mmr_result";
+
+/// Reads the computed end snapshot, with its full peaks, into the main execution scope, as inner
+/// scopes are dropped when exited.
+pub fn read_beacon_mmr_result(
+    vm: &mut VirtualMachine,
+    exec_scopes: &mut ExecutionScopes,
+    hint_data: &HintProcessorData,
+    _constants: &HashMap<String, Felt252>,
+) -> Result<(), HintError> {
+    let new_mmr_snapshot_ptr = get_relocatable_from_var_name(
+        "new_mmr_snapshot",
+        vm,
+        &hint_data.ids_data,
+        &hint_data.ap_tracking,
+    )?;
+    let new_mmr_snapshot = MmrSnapshotCairo::from_memory(vm, new_mmr_snapshot_ptr)?;
+    exec_scopes
+        .data
+        .first_mut()
+        .ok_or(ExecScopeError::NoScopeError)?
+        .insert(BEACON_MMR_RESULT.to_string(), Box::new(new_mmr_snapshot));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_vm_base::cairo_type::CairoWritable;
+    use cairo_vm_base::vm::cairo_vm::hint_processor::hint_processor_definition::HintReference;
+
+    use super::*;
+    use crate::test_utils::example_input;
+
+    #[test]
+    fn stores_the_result_in_the_main_scope() {
+        let snapshot = example_input().end_snapshot;
+        let mut vm = VirtualMachine::new(false);
+        vm.add_memory_segment();
+        let fp = vm.add_memory_segment();
+        vm.set_fp(0);
+        snapshot.to_memory(&mut vm, fp).unwrap();
+        let hint_data = HintProcessorData::new_default(
+            HINT_READ_BEACON_MMR_RESULT.to_string(),
+            HashMap::from([("new_mmr_snapshot".to_string(), HintReference::new_simple(0))]),
+        );
+
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.enter_scope(HashMap::new());
+        read_beacon_mmr_result(&mut vm, &mut exec_scopes, &hint_data, &HashMap::new()).unwrap();
+        exec_scopes.exit_scope().unwrap();
+
+        let result = exec_scopes
+            .get_ref::<MmrSnapshotCairo>(BEACON_MMR_RESULT)
+            .unwrap();
+        assert_eq!(result.poseidon_root.0, snapshot.poseidon_root.0);
+        assert_eq!(result.poseidon_peaks.len(), 2);
+    }
+}
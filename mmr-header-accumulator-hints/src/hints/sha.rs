@@ -0,0 +1,243 @@
+use cairo_vm_base::vm::cairo_vm::{
+    hint_processor::builtin_hint_processor::{
+        builtin_hint_processor_definition::HintProcessorData,
+        hint_utils::{get_constant_from_var_name, get_ptr_from_var_name},
+    },
+    types::{exec_scope::ExecutionScopes, relocatable::MaybeRelocatable},
+    vm::{
+        errors::{hint_errors::HintError, vm_errors::VirtualMachineError},
+        vm_core::VirtualMachine,
+    },
+    Felt252,
+};
+use num_traits::ToPrimitive;
+use sha2::{compress256, digest::generic_array::GenericArray};
+use std::collections::HashMap;
+
+const SHA256_INPUT_CHUNK_SIZE_FELTS: usize = 16;
+const SHA256_STATE_SIZE_FELTS: usize = 8;
+
+pub const HINT_SHA256_CHUNK: &str = r#"from starkware.cairo.common.cairo_sha256.sha256_utils import (
+    compute_message_schedule, sha2_compress_function)
+
+_sha256_input_chunk_size_felts = int(ids.SHA256_INPUT_CHUNK_SIZE_FELTS)
+assert 0 <= _sha256_input_chunk_size_felts < 100
+_sha256_state_size_felts = int(ids.SHA256_STATE_SIZE_FELTS)
+assert 0 <= _sha256_state_size_felts < 100
+w = compute_message_schedule(memory.get_range(
+    ids.sha256_start, _sha256_input_chunk_size_felts))
+new_state = sha2_compress_function(memory.get_range(ids.state, _sha256_state_size_felts), w)
+segments.write_arg(ids.output, new_state)"#;
+
+/// Compresses the 16 words at `sha256_start` into the 8 words of `state`, and writes the new
+/// state to `output`, as `_sha256_chunk` in `src/core/sha.cairo`.
+pub fn hint_sha256_chunk(
+    vm: &mut VirtualMachine,
+    _exec_scope: &mut ExecutionScopes,
+    hint_data: &HintProcessorData,
+    constants: &HashMap<String, Felt252>,
+) -> Result<(), HintError> {
+    let input_size = chunk_size(
+        "SHA256_INPUT_CHUNK_SIZE_FELTS",
+        SHA256_INPUT_CHUNK_SIZE_FELTS,
+        constants,
+    )?;
+    let state_size = chunk_size(
+        "SHA256_STATE_SIZE_FELTS",
+        SHA256_STATE_SIZE_FELTS,
+        constants,
+    )?;
+
+    let sha256_start = get_ptr_from_var_name(
+        "sha256_start",
+        vm,
+        &hint_data.ids_data,
+        &hint_data.ap_tracking,
+    )?;
+    let state_ptr =
+        get_ptr_from_var_name("state", vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
+    let output_ptr =
+        get_ptr_from_var_name("output", vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
+
+    let mut block = Vec::with_capacity(4 * input_size);
+    for word in vm.get_integer_range(sha256_start, input_size)? {
+        block.extend(felt_to_u32(&word)?.to_be_bytes());
+    }
+
+    let mut state = [0u32; SHA256_STATE_SIZE_FELTS];
+    for (word, value) in state
+        .iter_mut()
+        .zip(vm.get_integer_range(state_ptr, state_size)?)
+    {
+        *word = felt_to_u32(&value)?;
+    }
+
+    compress256(&mut state, &[GenericArray::clone_from_slice(&block)]);
+
+    let new_state: Vec<MaybeRelocatable> = state
+        .iter()
+        .map(|word| MaybeRelocatable::Int(Felt252::from(*word)))
+        .collect();
+    vm.write_arg(output_ptr, &new_state)
+        .map_err(VirtualMachineError::Memory)?;
+
+    Ok(())
+}
+
+/// Reads a chunk size constant. Any size below 100 passes the Python assertion, but the
+/// compression function only accepts the standard one.
+fn chunk_size(
+    name: &'static str,
+    expected: usize,
+    constants: &HashMap<String, Felt252>,
+) -> Result<usize, HintError> {
+    let value = get_constant_from_var_name(name, constants)?;
+    match value.to_usize() {
+        Some(size) if size == expected => Ok(size),
+        Some(size) if size < 100 => Err(HintError::InvalidValue(Box::new((
+            name,
+            *value,
+            Felt252::from(expected),
+        )))),
+        _ => Err(HintError::AssertionFailed(
+            format!("assert 0 <= {name} < 100").into_boxed_str(),
+        )),
+    }
+}
+
+fn felt_to_u32(value: &Felt252) -> Result<u32, HintError> {
+    value.to_u32().ok_or(HintError::BigintToU32Fail)
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_vm_base::vm::cairo_vm::types::relocatable::Relocatable;
+
+    use super::*;
+    use crate::test_utils::vm_with_ids;
+
+    const IV: [u32; 8] = [
+        0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB,
+        0x5BE0CD19,
+    ];
+
+    fn constants() -> HashMap<String, Felt252> {
+        HashMap::from([
+            (
+                "src.core.sha.SHA256_INPUT_CHUNK_SIZE_FELTS".to_string(),
+                Felt252::from(16),
+            ),
+            (
+                "src.core.sha.SHA256_STATE_SIZE_FELTS".to_string(),
+                Felt252::from(8),
+            ),
+        ])
+    }
+
+    fn felts(words: &[u32]) -> Vec<Felt252> {
+        words.iter().map(|word| Felt252::from(*word)).collect()
+    }
+
+    /// Lays out the block and the state as `sha256_inner` does, runs the hint and returns the
+    /// words written at `output`, checking that no other cell is written.
+    fn run_chunk(block: &[u32], state: &[u32]) -> Vec<u32> {
+        // Segment allocated right after the program and execution segments
+        let sha256_start = Relocatable::from((2, 0));
+        let state_ptr = (sha256_start + 16).unwrap();
+        let output_ptr = (sha256_start + 24).unwrap();
+        let (mut vm, ids_data) = vm_with_ids(&[
+            ("sha256_start", sha256_start.into()),
+            ("state", state_ptr.into()),
+            ("output", output_ptr.into()),
+        ]);
+        assert_eq!(vm.add_memory_segment(), sha256_start);
+        let inputs: Vec<MaybeRelocatable> = felts(block)
+            .into_iter()
+            .chain(felts(state))
+            .map(MaybeRelocatable::Int)
+            .collect();
+        vm.write_arg(sha256_start, &inputs).unwrap();
+
+        let hint_data = HintProcessorData::new_default(HINT_SHA256_CHUNK.to_string(), ids_data);
+        hint_sha256_chunk(
+            &mut vm,
+            &mut ExecutionScopes::new(),
+            &hint_data,
+            &constants(),
+        )
+        .unwrap();
+
+        let written: Vec<Felt252> = vm
+            .get_integer_range(sha256_start, 24)
+            .unwrap()
+            .iter()
+            .map(|word| **word)
+            .collect();
+        assert_eq!(written, [felts(block), felts(state)].concat());
+        assert_eq!(vm.segments.compute_effective_sizes()[2], 32);
+        vm.get_integer_range(output_ptr, 8)
+            .unwrap()
+            .iter()
+            .map(|word| word.to_u32().unwrap())
+            .collect()
+    }
+
+    fn words(bytes: &[u8]) -> Vec<u32> {
+        bytes
+            .chunks(4)
+            .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn compresses_a_single_padded_block() {
+        // "abc", padded with a one bit and its length in bits, as for messages up to 55 bytes
+        let mut block = [0u32; 16];
+        block[0] = 0x61626380;
+        block[15] = 3 * 8;
+
+        assert_eq!(
+            run_chunk(&block, &IV),
+            words(
+                &hex::decode("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn compresses_a_final_block_of_padding() {
+        // A 56 byte message only leaves room for the one bit in its block, the length goes into
+        // a last block made of padding only.
+        let message = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+        let mut first_block = words(message);
+        first_block.extend([0x80000000, 0]);
+        let state = run_chunk(&first_block, &IV);
+
+        let mut last_block = [0u32; 16];
+        last_block[15] = 56 * 8;
+        assert_eq!(
+            run_chunk(&last_block, &state),
+            words(
+                &hex::decode("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1")
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_non_standard_chunk_sizes() {
+        let mut constants = constants();
+        constants.insert(
+            "src.core.sha.SHA256_STATE_SIZE_FELTS".to_string(),
+            Felt252::from(100),
+        );
+        let (mut vm, ids_data) = vm_with_ids(&[]);
+        let hint_data = HintProcessorData::new_default(HINT_SHA256_CHUNK.to_string(), ids_data);
+
+        assert!(matches!(
+            hint_sha256_chunk(&mut vm, &mut ExecutionScopes::new(), &hint_data, &constants),
+            Err(HintError::AssertionFailed(_))
+        ));
+    }
+}
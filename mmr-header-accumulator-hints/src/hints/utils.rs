@@ -0,0 +1,196 @@
+use cairo_vm_base::vm::cairo_vm::{
+    hint_processor::builtin_hint_processor::{
+        builtin_hint_processor_definition::HintProcessorData,
+        hint_utils::{get_integer_from_var_name, insert_value_from_var_name},
+    },
+    types::{exec_scope::ExecutionScopes, relocatable::MaybeRelocatable},
+    vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
+    Felt252,
+};
+use starknet_types_core::felt::NonZeroFelt;
+use std::collections::HashMap;
+
+pub const HINT_FELT_DIVMOD: &str = r#"from starkware.cairo.common.math_utils import assert_integer
+assert_integer(ids.div)
+assert 0 < ids.div <= PRIME // range_check_builtin.bound, \
+    f'div={hex(ids.div)} is out of the valid range.'
+ids.q, ids.r = divmod(ids.value, ids.div)"#;
+
+/// Writes the quotient and remainder of `value` by `div`, as `felt_divmod` in
+/// `src/core/utils.cairo`.
+pub fn hint_felt_divmod(
+    vm: &mut VirtualMachine,
+    _exec_scope: &mut ExecutionScopes,
+    hint_data: &HintProcessorData,
+    _constants: &HashMap<String, Felt252>,
+) -> Result<(), HintError> {
+    let value =
+        get_integer_from_var_name("value", vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
+    let div = get_integer_from_var_name("div", vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
+    let bound = *vm.get_range_check_builtin()?.bound();
+
+    // PRIME // bound, the prime not being a multiple of the bound
+    let max_div = Felt252::from(&(Felt252::MAX.to_biguint() / bound.to_biguint()));
+    if div == Felt252::ZERO || div > max_div {
+        return Err(HintError::OutOfValidRange(Box::new((div, max_div))));
+    }
+
+    let (q, r) = value.div_rem(&NonZeroFelt::try_from(div).expect("div is not zero"));
+    insert_value_from_var_name(
+        "q",
+        MaybeRelocatable::Int(q),
+        vm,
+        &hint_data.ids_data,
+        &hint_data.ap_tracking,
+    )?;
+    insert_value_from_var_name(
+        "r",
+        MaybeRelocatable::Int(r),
+        vm,
+        &hint_data.ids_data,
+        &hint_data.ap_tracking,
+    )?;
+
+    Ok(())
+}
+
+pub const HINT_GET_FELT_BITLENGTH: &str = "ids.bit_length = ids.x.bit_length()";
+
+pub fn hint_get_felt_bitlength(
+    vm: &mut VirtualMachine,
+    _exec_scope: &mut ExecutionScopes,
+    hint_data: &HintProcessorData,
+    _constants: &HashMap<String, Felt252>,
+) -> Result<(), HintError> {
+    let x = get_integer_from_var_name("x", vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
+    insert_value_from_var_name(
+        "bit_length",
+        MaybeRelocatable::Int(x.bits().into()),
+        vm,
+        &hint_data.ids_data,
+        &hint_data.ap_tracking,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_vm_base::vm::cairo_vm::{
+        hint_processor::hint_processor_definition::HintReference, types::relocatable::Relocatable,
+        vm::runners::builtin_runner::RangeCheckBuiltinRunner,
+    };
+    use num_bigint::BigUint;
+
+    use super::*;
+    use crate::test_utils::vm_with_ids;
+
+    /// `PRIME // range_check_builtin.bound`, with a 2**128 bound
+    fn max_div() -> Felt252 {
+        Felt252::from_hex("0x8000000000000110000000000000000").unwrap()
+    }
+
+    /// Runs the divmod hint with `q` and `r` in the two cells after `value` and `div`, and returns
+    /// the execution segment.
+    fn run_divmod(
+        value: Felt252,
+        div: Felt252,
+    ) -> Result<Vec<Option<MaybeRelocatable>>, HintError> {
+        let (mut vm, mut ids_data) = vm_with_ids(&[("value", value.into()), ("div", div.into())]);
+        ids_data.insert("q".to_string(), HintReference::new_simple(2));
+        ids_data.insert("r".to_string(), HintReference::new_simple(3));
+        vm.builtin_runners
+            .push(RangeCheckBuiltinRunner::<8>::new(Some(8), true).into());
+
+        let hint_data = HintProcessorData::new_default(HINT_FELT_DIVMOD.to_string(), ids_data);
+        hint_felt_divmod(
+            &mut vm,
+            &mut ExecutionScopes::new(),
+            &hint_data,
+            &HashMap::new(),
+        )?;
+        Ok((0..5)
+            .map(|offset| vm.get_maybe(&Relocatable::from((1, offset))))
+            .collect())
+    }
+
+    fn int(value: impl Into<Felt252>) -> Option<MaybeRelocatable> {
+        Some(MaybeRelocatable::Int(value.into()))
+    }
+
+    #[test]
+    fn writes_the_quotient_and_remainder() {
+        assert_eq!(
+            run_divmod(Felt252::from(100), Felt252::from(7)).unwrap(),
+            [int(100), int(7), int(14), int(2), None]
+        );
+
+        // divmod(PRIME - 1, 2**64) on the integer representative, as in Python
+        let value = Felt252::MAX;
+        let div = Felt252::from(1u128 << 64);
+        let (q, r) = (
+            value.to_biguint() / div.to_biguint(),
+            value.to_biguint() % div.to_biguint(),
+        );
+        assert_eq!(r, BigUint::default());
+        assert_eq!(
+            run_divmod(value, div).unwrap(),
+            [
+                int(value),
+                int(div),
+                int(Felt252::from(&q)),
+                int(Felt252::ZERO),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_divisors_up_to_prime_over_bound() {
+        let div = max_div();
+        assert_eq!(
+            run_divmod(div, div).unwrap(),
+            [int(div), int(div), int(1), int(0), None]
+        );
+    }
+
+    #[test]
+    fn rejects_divisors_out_of_range() {
+        for div in [Felt252::ZERO, max_div() + Felt252::ONE] {
+            assert!(matches!(
+                run_divmod(Felt252::from(100), div),
+                Err(HintError::OutOfValidRange(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn writes_the_bit_length() {
+        for (x, bit_length) in [
+            (Felt252::ZERO, 0u64),
+            (Felt252::ONE, 1),
+            (Felt252::from(255), 8),
+            (Felt252::from(256), 9),
+            (Felt252::MAX, 252),
+        ] {
+            let (mut vm, mut ids_data) = vm_with_ids(&[("x", x.into())]);
+            ids_data.insert("bit_length".to_string(), HintReference::new_simple(1));
+            let hint_data =
+                HintProcessorData::new_default(HINT_GET_FELT_BITLENGTH.to_string(), ids_data);
+            hint_get_felt_bitlength(
+                &mut vm,
+                &mut ExecutionScopes::new(),
+                &hint_data,
+                &HashMap::new(),
+            )
+            .unwrap();
+
+            assert_eq!(
+                vm.get_maybe(&Relocatable::from((1, 1))),
+                int(bit_length),
+                "x = {x}"
+            );
+            assert_eq!(vm.get_maybe(&Relocatable::from((1, 2))), None);
+        }
+    }
+}
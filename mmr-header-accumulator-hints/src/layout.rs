@@ -0,0 +1,321 @@
+//! Checks that the Rust writers match the structs of the compiled Cairo program.
+//!
+//! Each writer lists the members it writes with `cairo_members()`. They are compared by name,
+//! offset and size with the struct identifiers of the program, so editing a Cairo struct without
+//! updating its Rust counterpart fails when the program is loaded instead of corrupting the input.
+
+use std::collections::HashMap;
+
+use cairo_vm_base::vm::cairo_vm::{
+    serde::deserialize_program::Identifier, types::program::Program,
+};
+use thiserror::Error;
+
+use crate::types::{BeaconHeaderCairo, LastLeafProofCairo, MmrSnapshotCairo};
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    #[error("Struct {0} was not found in the program identifiers")]
+    MissingStruct(String),
+    #[error("Cannot compute the size of member type {0}")]
+    UnknownType(String),
+    #[error("{cairo_struct} has members {cairo:?}, but the Rust writer writes {rust:?}")]
+    MembersMismatch {
+        cairo_struct: String,
+        cairo: Vec<String>,
+        rust: Vec<String>,
+    },
+    #[error("{cairo_struct}.{member} is at offset {cairo} in Cairo, but {rust} in Rust")]
+    OffsetMismatch {
+        cairo_struct: String,
+        member: String,
+        cairo: usize,
+        rust: usize,
+    },
+    #[error("{cairo_struct}.{member} has {cairo} fields in Cairo, but {rust} in Rust")]
+    SizeMismatch {
+        cairo_struct: String,
+        member: String,
+        cairo: usize,
+        rust: usize,
+    },
+}
+
+/// A Cairo struct along with the members written by its Rust counterpart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLayout {
+    /// Full path of the struct in the program identifiers
+    pub cairo_struct: &'static str,
+    pub members: Vec<(&'static str, usize)>,
+}
+
+/// Layouts of the structs written by `write_beacon_input`.
+pub fn input_layouts() -> Vec<StructLayout> {
+    vec![
+        StructLayout {
+            cairo_struct: "src.mmr.types.MmrSnapshot",
+            members: MmrSnapshotCairo::cairo_members(),
+        },
+        StructLayout {
+            cairo_struct: "src.mmr.types.LastLeafProof",
+            members: LastLeafProofCairo::cairo_members(),
+        },
+        StructLayout {
+            cairo_struct: "src.beacon.types.BeaconHeader",
+            members: BeaconHeaderCairo::cairo_members(),
+        },
+    ]
+}
+
+/// Checks the layouts of the input structs against the program identifiers.
+pub fn check_program_layouts(program: &Program) -> Result<(), LayoutError> {
+    let identifiers: HashMap<&str, &Identifier> = program.iter_identifiers().collect();
+    for layout in input_layouts() {
+        check_layout(&identifiers, &layout)?;
+    }
+    Ok(())
+}
+
+pub fn check_layout(
+    identifiers: &HashMap<&str, &Identifier>,
+    layout: &StructLayout,
+) -> Result<(), LayoutError> {
+    let members = struct_members(identifiers, layout.cairo_struct)?;
+
+    let cairo_names: Vec<String> = members.iter().map(|(name, _, _)| name.clone()).collect();
+    let rust_names: Vec<String> = layout
+        .members
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect();
+    if cairo_names != rust_names {
+        return Err(LayoutError::MembersMismatch {
+            cairo_struct: layout.cairo_struct.to_string(),
+            cairo: cairo_names,
+            rust: rust_names,
+        });
+    }
+
+    let mut rust_offset = 0;
+    for ((member, cairo_offset, cairo_size), (_, rust_size)) in members.iter().zip(&layout.members)
+    {
+        if *cairo_offset != rust_offset {
+            return Err(LayoutError::OffsetMismatch {
+                cairo_struct: layout.cairo_struct.to_string(),
+                member: member.clone(),
+                cairo: *cairo_offset,
+                rust: rust_offset,
+            });
+        }
+        if cairo_size != rust_size {
+            return Err(LayoutError::SizeMismatch {
+                cairo_struct: layout.cairo_struct.to_string(),
+                member: member.clone(),
+                cairo: *cairo_size,
+                rust: *rust_size,
+            });
+        }
+        rust_offset += rust_size;
+    }
+    Ok(())
+}
+
+/// Members of a struct as `(name, offset, size)`, sorted by offset.
+fn struct_members(
+    identifiers: &HashMap<&str, &Identifier>,
+    cairo_struct: &str,
+) -> Result<Vec<(String, usize, usize)>, LayoutError> {
+    let members = find_struct(identifiers, cairo_struct)
+        .and_then(|identifier| identifier.members.as_ref())
+        .ok_or_else(|| LayoutError::MissingStruct(cairo_struct.to_string()))?;
+
+    let mut members = members
+        .iter()
+        .map(|(name, member)| {
+            let size = type_size(identifiers, &member.cairo_type)?;
+            Ok((name.clone(), member.offset, size))
+        })
+        .collect::<Result<Vec<_>, LayoutError>>()?;
+    members.sort_by_key(|(_, offset, _)| *offset);
+    Ok(members)
+}
+
+/// Looks up a struct by its full path, or by its name in `__main__` where it may be imported.
+fn find_struct<'a>(
+    identifiers: &HashMap<&str, &'a Identifier>,
+    cairo_struct: &str,
+) -> Option<&'a Identifier> {
+    let name = cairo_struct.rsplit('.').next()?;
+    [cairo_struct.to_string(), format!("__main__.{name}")]
+        .iter()
+        .filter_map(|path| identifiers.get(path.as_str()))
+        .find(|identifier| identifier.type_.as_deref() == Some("struct"))
+        .copied()
+}
+
+fn type_size(
+    identifiers: &HashMap<&str, &Identifier>,
+    cairo_type: &str,
+) -> Result<usize, LayoutError> {
+    if cairo_type == "felt" || cairo_type.ends_with('*') {
+        return Ok(1);
+    }
+    let members = struct_members(identifiers, cairo_type)
+        .map_err(|_| LayoutError::UnknownType(cairo_type.to_string()))?;
+    Ok(members.iter().map(|(_, _, size)| size).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    const UINT256: &str = "starkware.cairo.common.uint256.Uint256";
+
+    fn member(cairo_type: &str, offset: usize) -> Value {
+        json!({"cairo_type": cairo_type, "offset": offset})
+    }
+
+    /// Identifiers of `MmrSnapshot` as compiled, along with `Uint256`.
+    fn snapshot_identifiers() -> Value {
+        json!({
+            UINT256: {
+                "type": "struct",
+                "full_name": UINT256,
+                "members": {"low": member("felt", 0), "high": member("felt", 1)},
+                "size": 2,
+            },
+            "src.mmr.types.MmrSnapshot": {
+                "type": "struct",
+                "full_name": "src.mmr.types.MmrSnapshot",
+                "members": {
+                    "keccak_root": member(UINT256, 0),
+                    "poseidon_root": member("felt", 2),
+                    "elements_count": member("felt", 3),
+                    "keccak_peaks": member(&format!("{UINT256}*"), 4),
+                    "poseidon_peaks": member("felt*", 5),
+                    "peaks_len": member("felt", 6),
+                },
+                "size": 7,
+            },
+        })
+    }
+
+    fn check_snapshot(identifiers: Value) -> Result<(), LayoutError> {
+        let identifiers: HashMap<String, Identifier> = serde_json::from_value(identifiers).unwrap();
+        let identifiers = identifiers
+            .iter()
+            .map(|(name, identifier)| (name.as_str(), identifier))
+            .collect();
+        check_layout(&identifiers, &input_layouts()[0])
+    }
+
+    fn snapshot_members(identifiers: &mut Value) -> &mut serde_json::Map<String, Value> {
+        identifiers["src.mmr.types.MmrSnapshot"]["members"]
+            .as_object_mut()
+            .unwrap()
+    }
+
+    #[test]
+    fn accepts_a_matching_struct() {
+        assert_eq!(check_snapshot(snapshot_identifiers()), Ok(()));
+
+        // Imported into the main scope
+        let mut identifiers = snapshot_identifiers();
+        let snapshot = identifiers
+            .as_object_mut()
+            .unwrap()
+            .remove("src.mmr.types.MmrSnapshot")
+            .unwrap();
+        identifiers["__main__.MmrSnapshot"] = snapshot;
+        assert_eq!(check_snapshot(identifiers), Ok(()));
+    }
+
+    #[test]
+    fn rejects_a_missing_struct() {
+        let mut identifiers = snapshot_identifiers();
+        identifiers
+            .as_object_mut()
+            .unwrap()
+            .remove("src.mmr.types.MmrSnapshot");
+        assert_eq!(
+            check_snapshot(identifiers),
+            Err(LayoutError::MissingStruct(
+                "src.mmr.types.MmrSnapshot".to_string()
+            ))
+        );
+
+        let mut identifiers = snapshot_identifiers();
+        identifiers.as_object_mut().unwrap().remove(UINT256);
+        assert_eq!(
+            check_snapshot(identifiers),
+            Err(LayoutError::UnknownType(UINT256.to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_reordered_members() {
+        let mut identifiers = snapshot_identifiers();
+        let members = snapshot_members(&mut identifiers);
+        members.insert("poseidon_root".to_string(), member("felt", 3));
+        members.insert("elements_count".to_string(), member("felt", 2));
+
+        let Err(LayoutError::MembersMismatch { cairo, rust, .. }) = check_snapshot(identifiers)
+        else {
+            panic!("expected a members mismatch");
+        };
+        assert_eq!(cairo[1..3], ["elements_count", "poseidon_root"]);
+        assert_eq!(rust[1..3], ["poseidon_root", "elements_count"]);
+    }
+
+    #[test]
+    fn rejects_missing_or_extra_members() {
+        let mut identifiers = snapshot_identifiers();
+        snapshot_members(&mut identifiers).remove("peaks_len");
+        assert!(matches!(
+            check_snapshot(identifiers),
+            Err(LayoutError::MembersMismatch { cairo, .. }) if cairo.len() == 5
+        ));
+
+        let mut identifiers = snapshot_identifiers();
+        snapshot_members(&mut identifiers).insert("extra".to_string(), member("felt", 7));
+        assert!(matches!(
+            check_snapshot(identifiers),
+            Err(LayoutError::MembersMismatch { cairo, .. }) if cairo.len() == 7
+        ));
+    }
+
+    #[test]
+    fn rejects_a_member_at_another_offset() {
+        let mut identifiers = snapshot_identifiers();
+        let members = snapshot_members(&mut identifiers);
+        for (name, offset) in [("keccak_peaks", 5), ("poseidon_peaks", 6), ("peaks_len", 7)] {
+            members[name]["offset"] = json!(offset);
+        }
+        assert_eq!(
+            check_snapshot(identifiers),
+            Err(LayoutError::OffsetMismatch {
+                cairo_struct: "src.mmr.types.MmrSnapshot".to_string(),
+                member: "keccak_peaks".to_string(),
+                cairo: 5,
+                rust: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_a_member_of_another_size() {
+        let mut identifiers = snapshot_identifiers();
+        snapshot_members(&mut identifiers)["keccak_root"]["cairo_type"] = json!("felt");
+        assert_eq!(
+            check_snapshot(identifiers),
+            Err(LayoutError::SizeMismatch {
+                cairo_struct: "src.mmr.types.MmrSnapshot".to_string(),
+                member: "keccak_root".to_string(),
+                cairo: 1,
+                rust: 2,
+            })
+        );
+    }
+}
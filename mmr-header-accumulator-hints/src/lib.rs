@@ -1,4 +1,15 @@
+pub mod audit;
+pub mod beacon;
+pub mod builtin_hints;
 pub mod error;
+pub mod failure;
 pub mod hint_processor;
 pub mod hints;
+pub mod layout;
+pub mod mmr;
+pub mod output;
+pub mod runner;
+pub mod summary;
+#[cfg(test)]
+mod test_utils;
 pub mod types;
@@ -1,159 +1,239 @@
 #![allow(clippy::result_large_err)]
-use cairo_vm_base::stwo_utils::FileWriter;
-use cairo_vm_base::vm::cairo_vm::{
-    cairo_run::{
-        self, cairo_run_program_with_initial_scope, write_encoded_memory, write_encoded_trace,
-    },
-    types::{exec_scope::ExecutionScopes, layout_name::LayoutName, program::Program},
-    vm::{
-        errors::trace_errors::TraceError, runners::cairo_pie::CairoPie,
-        runners::cairo_runner::CairoRunner,
-    },
-};
-use clap::Parser;
+use cairo_vm_base::vm::cairo_vm::types::layout_name::LayoutName;
+use clap::{Parser, Subcommand, ValueEnum};
 use mmr_header_accumulator_hints::{
-    error::Error, hint_processor::CustomHintProcessor, types::BeaconMmrUpdateCairo,
+    audit::audit_program_file,
+    beacon::update::build_beacon_mmr_update,
+    error::Error,
+    hint_processor::CustomHintProcessor,
+    mmr::file_store::FileNodeStore,
+    runner::{resolve_program_path, Runner, RunnerOptions},
+    types::{BeaconHeaderCairo, BeaconMmrUpdateCairo, MmrStartState},
 };
-use std::{io, path::Path, path::PathBuf};
+use std::path::{Path, PathBuf};
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
-    #[arg(short, long)]
-    input_path: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[arg(short, long, required = true)]
+    input_path: Option<PathBuf>,
+    /// What to produce from the run
+    #[arg(long, env = "MMR_MODE", value_enum, default_value_t = Mode::Pie)]
+    mode: Mode,
+    /// Layout used by the Stone mode
+    #[arg(long, env = "MMR_LAYOUT", value_enum, default_value_t = StoneLayout::StarknetWithKeccak)]
+    layout: StoneLayout,
+    /// Compiled Cairo program, resolved against the working directory when relative.
+    /// Defaults to `../build/main.json`, or `../build/main_proof.json` for the proving modes
+    #[arg(long, env = "MMR_PROGRAM")]
+    program: Option<PathBuf>,
+    /// Directory the PIE or the prover inputs are written to, created if missing
+    #[arg(long, env = "MMR_OUTPUT_DIR", default_value = "../output")]
+    output_dir: PathBuf,
+    /// File name of the PIE inside the output directory
+    #[arg(long, env = "MMR_PIE_NAME", default_value = "pie.zip")]
+    pie_name: String,
+    /// File name of the JSON run summary inside the output directory
+    #[arg(long, env = "MMR_SUMMARY_NAME", default_value = "summary.json")]
+    summary_name: String,
+    /// Node store directory to append the headers to once the run succeeds
+    #[arg(long)]
+    store: Option<PathBuf>,
+    /// Run without checking first that every hint of the program can be executed
+    #[arg(long)]
+    skip_hint_audit: bool,
+    /// Skip the debug prints of the program instead of showing them after the run
+    #[arg(long, env = "MMR_SILENCE_DEBUG")]
+    silence_debug: bool,
 }
 
-fn load_program(path: &str) -> Result<Program, Error> {
-    // Check if it's an absolute path that doesn't exist, try relative
-    let final_path = if path.starts_with('/') && !std::path::Path::new(path).exists() {
-        // Try converting absolute path to relative
-        let relative_path = path.strip_prefix('/').unwrap_or(path);
-        println!(
-            "Absolute path not found, trying relative: {}",
-            relative_path
-        );
-        relative_path
-    } else {
-        path
-    };
-
-    let program_file = std::fs::read(final_path).map_err(Error::IO)?;
-    let cairo_run_config = cairo_run::CairoRunConfig {
-        allow_missing_builtins: Some(true),
-        layout: LayoutName::all_cairo,
-        ..Default::default()
-    };
-
-    let program = Program::from_bytes(&program_file, Some(cairo_run_config.entrypoint))?;
-    println!("Program loaded successfully");
-    Ok(program)
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    /// Run with the all_cairo layout and write a Cairo PIE
+    Pie,
+    /// Run in proof mode with the all_cairo_stwo layout and write the Stwo prover inputs
+    Stwo,
+    /// Run in proof mode with the selected layout and write the Stone prover inputs
+    Stone,
 }
 
-pub fn run_stwo(path: &str, input: BeaconMmrUpdateCairo, output_dir: &str) -> Result<(), Error> {
-    let program = load_program(path)?;
-    let cairo_run_config = cairo_run::CairoRunConfig {
-        allow_missing_builtins: None, // Optional
-        layout: LayoutName::all_cairo_stwo,
-        relocate_mem: true,
-        trace_enabled: true,
-        proof_mode: true,
-        ..Default::default()
-    };
-
-    let mut hint_processor = CustomHintProcessor::new();
-    let mut exec_scopes = ExecutionScopes::new();
-    exec_scopes.insert_value("beacon_input", input);
+impl Mode {
+    fn default_program(self) -> &'static str {
+        match self {
+            Mode::Pie => "../build/main.json",
+            Mode::Stwo | Mode::Stone => "../build/main_proof.json",
+        }
+    }
+}
 
-    let cairo_runner = cairo_run_program_with_initial_scope(
-        &program,
-        &cairo_run_config,
-        &mut hint_processor,
-        exec_scopes,
-    )?;
+/// Stone layouts the program can be proven with, i.e. the ones providing the keccak and poseidon
+/// builtins of the program.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[value(rename_all = "snake_case")]
+enum StoneLayout {
+    StarknetWithKeccak,
+    AllCairo,
+}
 
-    // tracing::info!("{:?}", cairo_runner.get_execution_resources());
+impl From<StoneLayout> for LayoutName {
+    fn from(layout: StoneLayout) -> Self {
+        match layout {
+            StoneLayout::StarknetWithKeccak => LayoutName::starknet_with_keccak,
+            StoneLayout::AllCairo => LayoutName::all_cairo,
+        }
+    }
+}
 
-    generate_stwo_files(&cairo_runner, output_dir)?;
-    Ok(())
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Build a BeaconMmrUpdateCairo input from a start state and a list of headers
+    GenerateInput {
+        /// JSON file containing the MmrStartState
+        #[arg(short, long, required_unless_present = "store")]
+        start_state: Option<PathBuf>,
+        /// Node store directory to read the start state from
+        #[arg(long, conflicts_with = "start_state")]
+        store: Option<PathBuf>,
+        /// JSON file containing the list of BeaconHeaderCairo to append
+        #[arg(long)]
+        headers: PathBuf,
+        /// Where to write the generated input
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// List the hints of a compiled program that the hint processor cannot execute
+    AuditHints {
+        /// Compiled Cairo program, resolved against the working directory when relative
+        #[arg(long, env = "MMR_PROGRAM", default_value = "../build/main.json")]
+        program: PathBuf,
+    },
 }
 
-pub fn run(path: &str, input: BeaconMmrUpdateCairo) -> Result<CairoPie, Error> {
-    let program = load_program(path)?;
-    let cairo_run_config = cairo_run::CairoRunConfig {
-        allow_missing_builtins: Some(true),
-        layout: LayoutName::all_cairo,
-        ..Default::default()
+fn generate_input(
+    start_state: Option<&Path>,
+    store: Option<&Path>,
+    headers: &Path,
+    output: &Path,
+) -> Result<(), Error> {
+    let headers: Vec<BeaconHeaderCairo> = serde_json::from_str(&std::fs::read_to_string(headers)?)?;
+
+    let update = match (start_state, store) {
+        (_, Some(store)) => FileNodeStore::open(store)?.build_update(&headers)?,
+        (Some(start_state), None) => {
+            let start_state: MmrStartState =
+                serde_json::from_str(&std::fs::read_to_string(start_state)?)?;
+            build_beacon_mmr_update(&start_state, &headers)?
+        }
+        (None, None) => unreachable!("clap requires either --start-state or --store"),
     };
-    let mut hint_processor = CustomHintProcessor::new();
-    let mut exec_scopes = ExecutionScopes::new();
-    exec_scopes.insert_value("beacon_mmr_update", input);
-
-    let cairo_runner = cairo_run_program_with_initial_scope(
-        &program,
-        &cairo_run_config,
-        &mut hint_processor,
-        exec_scopes,
-    )?;
-
-    println!("Resources: {:?}", cairo_runner.get_execution_resources());
-
-    let pie = cairo_runner.get_cairo_pie()?;
-    Ok(pie)
+    std::fs::write(output, serde_json::to_string_pretty(&update)?)?;
+    println!(
+        "Input written to {} ({} headers)",
+        output.display(),
+        headers.len()
+    );
+    Ok(())
 }
 
-fn generate_stwo_files(cairo_runner: &CairoRunner, output_dir: &str) -> Result<(), Error> {
-    std::fs::create_dir_all(output_dir)?;
-
-    let memory_path = Path::new(output_dir).join("memory.bin");
-    let memory_file = std::fs::File::create(&memory_path)?;
-    let mut memory_writer =
-        FileWriter::new(io::BufWriter::with_capacity(50 * 1024 * 1024, memory_file));
-    write_encoded_memory(&cairo_runner.relocated_memory, &mut memory_writer)?;
-    memory_writer.flush()?;
-
-    let trace_path = Path::new(output_dir).join("trace.bin");
-    let relocated_trace = cairo_runner
-        .relocated_trace
-        .as_ref()
-        .ok_or(Error::Trace(TraceError::TraceNotRelocated))?;
-    let trace_file = std::fs::File::create(&trace_path)?;
-    let mut trace_writer =
-        FileWriter::new(io::BufWriter::with_capacity(3 * 1024 * 1024, trace_file));
-    write_encoded_trace(relocated_trace, &mut trace_writer)?;
-    trace_writer.flush()?;
-
-    // 1. Generate air_public_inputs.json
-    let public_input = cairo_runner.get_air_public_input();
-    let public_input_json = serde_json::to_string_pretty(&public_input.unwrap()).unwrap();
-    std::fs::write(
-        Path::new(output_dir).join("air_public_inputs.json"),
-        public_input_json,
-    )?;
-
-    // 2. Generate air_private_inputs.json (after binary files are created)
-    let private_input = cairo_runner.get_air_private_input();
-    let private_input_serializable =
-        private_input.to_serializable("trace.bin".to_string(), "memory.bin".to_string());
-    let private_input_json = serde_json::to_string_pretty(&private_input_serializable).unwrap();
-    std::fs::write(
-        Path::new(output_dir).join("air_private_inputs.json"),
-        private_input_json,
-    )?;
+fn audit_hints(program: &Path) -> Result<(), Error> {
+    let audit = audit_program_file(&resolve_program_path(program)?, &CustomHintProcessor::new())?;
+    println!(
+        "{} hints, {} unhandled",
+        audit.hints.len(),
+        audit.unhandled.len()
+    );
+    for usage in &audit.unhandled {
+        println!("\nUnhandled hint at pcs {:?}:\n{}", usage.pcs, usage.code);
+    }
+    audit.ensure_handled()
+}
 
+fn run_update(args: Args) -> Result<(), Error> {
+    let input_path = args
+        .input_path
+        .expect("clap requires --input-path without a subcommand");
+    let input_str = std::fs::read_to_string(input_path)?;
+    let input: BeaconMmrUpdateCairo = serde_json::from_str(&input_str)?;
+
+    input.validate().map_err(Error::InvalidInput)?;
+
+    let program = args
+        .program
+        .unwrap_or_else(|| PathBuf::from(args.mode.default_program()));
+    let options = match args.mode {
+        Mode::Pie => RunnerOptions::pie()
+            .with_output_dir(&args.output_dir)
+            .with_pie_name(&args.pie_name),
+        Mode::Stwo => RunnerOptions::stwo(&args.output_dir),
+        Mode::Stone => RunnerOptions::stone(args.layout.into(), &args.output_dir),
+    }
+    .with_summary_name(&args.summary_name)
+    .with_hint_audit(!args.skip_hint_audit)
+    .with_silenced_debug_prints(args.silence_debug);
+    let outcome = Runner::from_file(&program, options)?.run(input.clone())?;
+
+    for print in &outcome.debug_log {
+        println!("[pc={}] {}", print.pc, print);
+    }
+    println!("Resources: {:?}", outcome.resources);
+    println!("Program hash: {}", outcome.summary.program_hash);
+    println!("Output: {:?}", outcome.mmr_update_output()?);
+    println!(
+        "End peaks: {} (MMR size {})",
+        outcome.end_snapshot.poseidon_peaks.len(),
+        outcome.end_snapshot.elements_count.0
+    );
+    let artifacts = &outcome.artifacts;
+    for path in [
+        &artifacts.pie,
+        &artifacts.memory,
+        &artifacts.trace,
+        &artifacts.air_public_input,
+        &artifacts.air_private_input,
+        &artifacts.summary,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        println!("Wrote {}", path.display());
+    }
+
+    if let Some(store) = args.store {
+        let mut store = FileNodeStore::open(store)?;
+        store.append_update(&input)?;
+        println!("Store updated to MMR size {}", store.elements_count());
+    }
     Ok(())
 }
 
 fn main() {
-    let args = Args::parse();
-    let input_str = std::fs::read_to_string(args.input_path).unwrap();
-    let input: BeaconMmrUpdateCairo = serde_json::from_str(&input_str).unwrap();
-
-    println!("got input");
-
-    let output_dir: &'static str = "../output/";
-    let program_path = "../build/main.json";
-    let pie = run(program_path, input.clone()).unwrap();
+    let mut args = Args::parse();
+    let result = match args.command.take() {
+        Some(Command::GenerateInput {
+            start_state,
+            store,
+            headers,
+            output,
+        }) => generate_input(start_state.as_deref(), store.as_deref(), &headers, &output),
+        Some(Command::AuditHints { program }) => audit_hints(&program),
+        None => run_update(args),
+    };
 
-    pie.write_zip_file(&Path::new(output_dir).join("pie.zip"), true)
-        .unwrap();
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+        // Failed checks keep the Cairo exception, with the location in the program
+        if let Some(source) = std::error::Error::source(&err) {
+            eprintln!("{}", source);
+        }
+        std::process::exit(err.exit_code());
+    }
 }
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+
+use alloy_primitives::B256;
+use cairo_vm_base::types::felt::Felt;
+use cairo_vm_base::vm::cairo_vm::Felt252;
+
+use crate::mmr::error::MmrError;
+use crate::mmr::hash::{KeccakHasher, MmrHasher, PoseidonHasher};
+use crate::mmr::utils::{compute_height, compute_peaks_positions, is_valid_mmr_size};
+use crate::types::{b256_to_uint256, MmrSnapshotCairo};
+
+/// Native replica of the Cairo MMR growth logic for a single hash function.
+///
+/// Starting from the peaks of an MMR, leaves are appended exactly like `construct_mmr`
+/// and `merge_subtrees_if_applicable` do.
+#[derive(Debug, Clone)]
+pub struct Mmr<H: MmrHasher> {
+    /// Size of the start MMR. `mmr_array[i]` is the node at position `i + mmr_offset + 1`.
+    mmr_offset: u128,
+    peaks_dict: HashMap<u128, H::Node>,
+    mmr_array: Vec<H::Node>,
+}
+
+impl<H: MmrHasher> Mmr<H> {
+    /// Starts from an MMR of size `mmr_offset` with the given peaks, from left to right.
+    /// `mmr_offset` must be a valid MMR size, and `peaks` must match its peak count.
+    pub fn new(mmr_offset: u128, peaks: &[H::Node]) -> Result<Self, MmrError> {
+        if !is_valid_mmr_size(mmr_offset) {
+            return Err(MmrError::InvalidSize(mmr_offset));
+        }
+        let peaks_positions = compute_peaks_positions(mmr_offset);
+        if peaks.len() != peaks_positions.len() {
+            return Err(MmrError::PeakCountMismatch {
+                size: mmr_offset,
+                expected: peaks_positions.len(),
+                actual: peaks.len(),
+            });
+        }
+        Ok(Self {
+            mmr_offset,
+            peaks_dict: peaks_positions
+                .into_iter()
+                .zip(peaks.iter().copied())
+                .collect(),
+            mmr_array: Vec::new(),
+        })
+    }
+
+    /// Starts from an empty MMR, which has no root until a leaf is appended.
+    pub(crate) fn empty() -> Self {
+        Self {
+            mmr_offset: 0,
+            peaks_dict: HashMap::new(),
+            mmr_array: Vec::new(),
+        }
+    }
+
+    /// Current number of elements in the MMR.
+    pub fn elements_count(&self) -> u128 {
+        self.mmr_offset + self.mmr_array.len() as u128
+    }
+
+    /// Size of the MMR this one was started from.
+    pub fn mmr_offset(&self) -> u128 {
+        self.mmr_offset
+    }
+
+    /// Nodes appended since the start, in position order starting at `mmr_offset + 1`.
+    pub fn appended_nodes(&self) -> &[H::Node] {
+        &self.mmr_array
+    }
+
+    /// Appends an already hashed leaf, merging subtrees as needed.
+    pub fn append(&mut self, leaf: H::Node) {
+        self.mmr_array.push(leaf);
+        self.merge_subtrees_if_applicable();
+    }
+
+    fn merge_subtrees_if_applicable(&mut self) {
+        let mut height = 0;
+        loop {
+            let next_pos = self.elements_count() + 1;
+            if compute_height(next_pos) != height + 1 {
+                // Next position is not a parent, no need to merge.
+                return;
+            }
+
+            let left_pos = next_pos - (1u128 << (height + 1));
+            let right_pos = next_pos - 1;
+            let parent = H::merge(
+                self.get_full_mmr_peak_value(left_pos),
+                self.get_full_mmr_peak_value(right_pos),
+            );
+            self.mmr_array.push(parent);
+            height += 1;
+        }
+    }
+
+    /// Returns the node at `position`, either from the appended nodes or from the start peaks.
+    /// Only called on positions that are peaks of the current MMR.
+    fn get_full_mmr_peak_value(&self, position: u128) -> H::Node {
+        if position > self.mmr_offset {
+            self.mmr_array[(position - self.mmr_offset - 1) as usize]
+        } else {
+            self.peaks_dict[&position]
+        }
+    }
+
+    /// Peaks from left to right.
+    pub fn peaks(&self) -> Vec<H::Node> {
+        compute_peaks_positions(self.elements_count())
+            .into_iter()
+            .map(|pos| self.get_full_mmr_peak_value(pos))
+            .collect()
+    }
+
+    /// Root of the MMR: H(mmr_size, bag_peaks).
+    pub fn root(&self) -> H::Node {
+        // A valid MMR always has at least one peak.
+        H::root_from_peaks(self.elements_count(), &self.peaks()).expect("MMR has no peaks")
+    }
+}
+
+/// Native replica of the Cairo MMR growth logic for both the Poseidon and Keccak MMRs.
+///
+/// Leaves are appended to both MMRs at once, so the resulting snapshot is the one the
+/// Cairo program accepts as `end_snapshot`.
+#[derive(Debug, Clone)]
+pub struct MmrAccumulator {
+    poseidon: Mmr<PoseidonHasher>,
+    keccak: Mmr<KeccakHasher>,
+}
+
+impl MmrAccumulator {
+    /// Starts from an MMR of size `mmr_offset` with the given peaks, without checking them against any root.
+    /// `mmr_offset` must be a valid MMR size, and both peak lists must match its peak count.
+    pub fn new(
+        mmr_offset: u128,
+        peaks_poseidon: &[Felt252],
+        peaks_keccak: &[B256],
+    ) -> Result<Self, MmrError> {
+        if !is_valid_mmr_size(mmr_offset) {
+            return Err(MmrError::InvalidSize(mmr_offset));
+        }
+        let expected = compute_peaks_positions(mmr_offset).len();
+        if peaks_poseidon.len() != expected || peaks_keccak.len() != expected {
+            return Err(MmrError::PeakListsCountMismatch {
+                size: mmr_offset,
+                expected,
+                poseidon: peaks_poseidon.len(),
+                keccak: peaks_keccak.len(),
+            });
+        }
+        Ok(Self {
+            poseidon: Mmr::new(mmr_offset, peaks_poseidon)?,
+            keccak: Mmr::new(mmr_offset, peaks_keccak)?,
+        })
+    }
+
+    /// Starts from an empty MMR, as when initializing a store.
+    pub(crate) fn empty() -> Self {
+        Self {
+            poseidon: Mmr::empty(),
+            keccak: Mmr::empty(),
+        }
+    }
+
+    /// Loads the peaks of `snapshot`, checking its size, peak count and roots like `initialize_peaks`.
+    pub fn from_snapshot(snapshot: &MmrSnapshotCairo) -> Result<Self, MmrError> {
+        snapshot.check_roots()?;
+
+        let peaks = snapshot.peaks_by_position()?;
+        let elements_count = snapshot.elements_count()?;
+        let (peaks_poseidon, peaks_keccak): (Vec<Felt252>, Vec<B256>) = peaks
+            .into_iter()
+            .map(|(_, poseidon, keccak)| (poseidon, keccak))
+            .unzip();
+        Self::new(elements_count, &peaks_poseidon, &peaks_keccak)
+    }
+
+    /// The Poseidon MMR.
+    pub fn poseidon(&self) -> &Mmr<PoseidonHasher> {
+        &self.poseidon
+    }
+
+    /// The Keccak MMR.
+    pub fn keccak(&self) -> &Mmr<KeccakHasher> {
+        &self.keccak
+    }
+
+    /// Current number of elements in the MMR.
+    pub fn elements_count(&self) -> u128 {
+        self.poseidon.elements_count()
+    }
+
+    /// Size of the MMR this accumulator was started from.
+    pub fn mmr_offset(&self) -> u128 {
+        self.poseidon.mmr_offset()
+    }
+
+    /// Nodes appended since the start snapshot, in position order starting at `mmr_offset + 1`.
+    pub fn appended_nodes(&self) -> (&[Felt252], &[B256]) {
+        (self.poseidon.appended_nodes(), self.keccak.appended_nodes())
+    }
+
+    /// Hashes a header root into both leaves and appends them.
+    pub fn append_header_root(&mut self, header_root: B256) {
+        self.append(
+            PoseidonHasher::hash_leaf(header_root),
+            KeccakHasher::hash_leaf(header_root),
+        );
+    }
+
+    /// Appends already hashed leaves to both MMRs, merging subtrees as needed.
+    pub fn append(&mut self, poseidon_leaf: Felt252, keccak_leaf: B256) {
+        self.poseidon.append(poseidon_leaf);
+        self.keccak.append(keccak_leaf);
+    }
+
+    /// Peaks of both MMRs from left to right.
+    pub fn peaks(&self) -> (Vec<Felt252>, Vec<B256>) {
+        (self.poseidon.peaks(), self.keccak.peaks())
+    }
+
+    /// Roots of both MMRs: H(mmr_size, bag_peaks).
+    pub fn roots(&self) -> (Felt252, B256) {
+        (self.poseidon.root(), self.keccak.root())
+    }
+
+    /// Snapshot of the current state, in the format expected by the Cairo program.
+    pub fn snapshot(&self) -> MmrSnapshotCairo {
+        let (peaks_poseidon, peaks_keccak) = self.peaks();
+        let (poseidon_root, keccak_root) = self.roots();
+        MmrSnapshotCairo {
+            keccak_root: b256_to_uint256(keccak_root),
+            poseidon_root: Felt(poseidon_root),
+            elements_count: Felt(Felt252::from(self.elements_count())),
+            keccak_peaks: peaks_keccak.into_iter().map(b256_to_uint256).collect(),
+            poseidon_peaks: peaks_poseidon.into_iter().map(Felt).collect(),
+        }
+    }
+}
+
+/// Appends `header_roots` to the MMR described by `start_snapshot` and returns the resulting snapshot.
+pub fn grow_mmr(
+    start_snapshot: &MmrSnapshotCairo,
+    header_roots: &[B256],
+) -> Result<MmrSnapshotCairo, MmrError> {
+    let mut accumulator = MmrAccumulator::from_snapshot(start_snapshot)?;
+    for header_root in header_roots {
+        accumulator.append_header_root(*header_root);
+    }
+    Ok(accumulator.snapshot())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beacon::ssz::hash_header_root;
+    use crate::test_utils::example_input;
+    use crate::types::uint256_to_b256;
+
+    fn peaks(snapshot: &MmrSnapshotCairo) -> (Vec<Felt252>, Vec<B256>) {
+        (
+            snapshot.poseidon_peaks.iter().map(|peak| peak.0).collect(),
+            snapshot.keccak_peaks.iter().map(uint256_to_b256).collect(),
+        )
+    }
+
+    #[test]
+    fn grows_the_example_start_snapshot_to_its_end_snapshot() {
+        let input = example_input();
+        let header_roots: Vec<B256> = input.added_headers.iter().map(hash_header_root).collect();
+
+        let end_snapshot = grow_mmr(&input.start_snapshot, &header_roots).unwrap();
+
+        assert_eq!(end_snapshot.elements_count().unwrap(), 64);
+        assert_eq!(
+            end_snapshot.poseidon_root.0,
+            input.end_snapshot.poseidon_root.0
+        );
+        assert_eq!(end_snapshot.keccak_root.0, input.end_snapshot.keccak_root.0);
+        assert_eq!(peaks(&end_snapshot), peaks(&input.end_snapshot));
+    }
+
+    #[test]
+    fn rejects_a_peak_count_mismatch() {
+        let (peaks_poseidon, peaks_keccak) = peaks(&example_input().end_snapshot);
+
+        assert_eq!(
+            MmrAccumulator::new(64, &peaks_poseidon[..1], &peaks_keccak).unwrap_err(),
+            MmrError::PeakListsCountMismatch {
+                size: 64,
+                expected: 2,
+                poseidon: 1,
+                keccak: 2,
+            }
+        );
+        assert_eq!(
+            Mmr::<KeccakHasher>::new(64, &peaks_keccak[..1]).unwrap_err(),
+            MmrError::PeakCountMismatch {
+                size: 64,
+                expected: 2,
+                actual: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_an_empty_mmr() {
+        assert_eq!(
+            MmrAccumulator::new(0, &[], &[]).unwrap_err(),
+            MmrError::InvalidSize(0)
+        );
+        assert_eq!(
+            Mmr::<PoseidonHasher>::new(0, &[]).unwrap_err(),
+            MmrError::InvalidSize(0)
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use alloy_primitives::B256;
+use cairo_vm_base::types::felt::Felt;
+use cairo_vm_base::vm::cairo_vm::Felt252;
+
+use crate::mmr::error::MmrError;
+use crate::mmr::hash::{KeccakHasher, MmrHasher, PoseidonHasher};
+use crate::mmr::proof::sibling_path;
+use crate::mmr::store::NodeStore;
+use crate::mmr::utils::{compute_height, compute_peaks_positions, is_valid_mmr_size};
+use crate::mmr::verify::hash_subtree_path;
+use crate::types::{
+    b256_to_uint256, felt_to_u128, uint256_to_b256, ConsistencyProof, MmrSnapshotCairo,
+};
+
+/// Builds the proof that the stored MMR of size `new_size` extends its historical state of size `old_size`.
+pub fn consistency_proof<S: NodeStore>(
+    store: &S,
+    old_size: u128,
+    new_size: u128,
+) -> Result<ConsistencyProof, MmrError> {
+    check_sizes(old_size, new_size)?;
+
+    let mut poseidon_paths = Vec::new();
+    let mut keccak_paths = Vec::new();
+    for old_peak in compute_peaks_positions(old_size) {
+        let (poseidon_path, keccak_path) = sibling_path(store, old_peak, new_size)?;
+        poseidon_paths.push(poseidon_path.into_iter().map(Felt).collect());
+        keccak_paths.push(keccak_path.into_iter().map(b256_to_uint256).collect());
+    }
+
+    Ok(ConsistencyProof {
+        old_elements_count: Felt(Felt252::from(old_size)),
+        new_elements_count: Felt(Felt252::from(new_size)),
+        poseidon_paths,
+        keccak_paths,
+    })
+}
+
+/// Verifies that `new_snapshot` extends `old_snapshot`: both snapshots must be consistent with their
+/// roots, and every old peak must hash up to a peak of the new MMR for both hash functions.
+pub fn verify_consistency_proof(
+    proof: &ConsistencyProof,
+    old_snapshot: &MmrSnapshotCairo,
+    new_snapshot: &MmrSnapshotCairo,
+) -> Result<(), MmrError> {
+    old_snapshot.check_roots()?;
+    new_snapshot.check_roots()?;
+
+    let old_size = old_snapshot.elements_count()?;
+    let new_size = new_snapshot.elements_count()?;
+    check_sizes(old_size, new_size)?;
+    let proof_old_size = felt_to_u128(&proof.old_elements_count)?;
+    let proof_new_size = felt_to_u128(&proof.new_elements_count)?;
+    if proof_old_size != old_size || proof_new_size != new_size {
+        return Err(MmrError::ConsistencySizeMismatch {
+            proof_old: proof_old_size,
+            proof_new: proof_new_size,
+            old: old_size,
+            new: new_size,
+        });
+    }
+
+    let old_peaks = old_snapshot.peaks_by_position()?;
+    let new_peaks = new_snapshot.peaks_by_position()?;
+    if proof.poseidon_paths.len() != old_peaks.len() || proof.keccak_paths.len() != old_peaks.len()
+    {
+        return Err(MmrError::ConsistencyPathsCountMismatch {
+            expected: old_peaks.len(),
+            poseidon: proof.poseidon_paths.len(),
+            keccak: proof.keccak_paths.len(),
+        });
+    }
+
+    verify_paths::<PoseidonHasher>(
+        old_peaks.iter().map(|(pos, poseidon, _)| (*pos, *poseidon)),
+        &new_peaks
+            .iter()
+            .map(|(pos, poseidon, _)| (*pos, *poseidon))
+            .collect::<Vec<_>>(),
+        new_size,
+        proof
+            .poseidon_paths
+            .iter()
+            .map(|path| path.iter().map(|node| node.0).collect::<Vec<Felt252>>()),
+        |position, expected, computed| MmrError::PoseidonPeakMismatch {
+            position,
+            expected,
+            computed,
+        },
+    )?;
+
+    verify_paths::<KeccakHasher>(
+        old_peaks.iter().map(|(pos, _, keccak)| (*pos, *keccak)),
+        &new_peaks
+            .iter()
+            .map(|(pos, _, keccak)| (*pos, *keccak))
+            .collect::<Vec<_>>(),
+        new_size,
+        proof
+            .keccak_paths
+            .iter()
+            .map(|path| path.iter().map(uint256_to_b256).collect::<Vec<B256>>()),
+        |position, expected, computed| MmrError::KeccakPeakMismatch {
+            position,
+            expected,
+            computed,
+        },
+    )
+}
+
+fn verify_paths<H: MmrHasher>(
+    old_peaks: impl Iterator<Item = (u128, H::Node)>,
+    new_peaks: &[(u128, H::Node)],
+    new_size: u128,
+    paths: impl Iterator<Item = Vec<H::Node>>,
+    peak_mismatch: fn(u128, H::Node, H::Node) -> MmrError,
+) -> Result<(), MmrError> {
+    for ((old_pos, old_peak), path) in old_peaks.zip(paths) {
+        let (peak, peak_pos, _) =
+            hash_subtree_path::<H>(old_peak, compute_height(old_pos), old_pos, &path, new_size)?;
+        let (_, expected) = new_peaks
+            .iter()
+            .find(|(pos, _)| *pos == peak_pos)
+            .ok_or(MmrError::NotAPeak(peak_pos))?;
+        if *expected != peak {
+            return Err(peak_mismatch(peak_pos, *expected, peak));
+        }
+    }
+    Ok(())
+}
+
+fn check_sizes(old_size: u128, new_size: u128) -> Result<(), MmrError> {
+    if !is_valid_mmr_size(old_size) {
+        return Err(MmrError::InvalidSize(old_size));
+    }
+    if !is_valid_mmr_size(new_size) {
+        return Err(MmrError::InvalidSize(new_size));
+    }
+    if old_size > new_size {
+        return Err(MmrError::ConsistencyRange {
+            old: old_size,
+            new: new_size,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mmr::store::MemoryNodeStore;
+    use crate::test_utils::mmr_with_leaves;
+
+    /// Store of an MMR of 16 leaves and its snapshot after each of them.
+    fn snapshots() -> (MemoryNodeStore, Vec<MmrSnapshotCairo>) {
+        let (_, store) = mmr_with_leaves(16);
+        let snapshots = (1..=16)
+            .map(|leaf_count| mmr_with_leaves(leaf_count).0.snapshot())
+            .collect();
+        (store, snapshots)
+    }
+
+    fn size(snapshot: &MmrSnapshotCairo) -> u128 {
+        snapshot.elements_count().unwrap()
+    }
+
+    #[test]
+    fn proves_every_older_snapshot() {
+        let (store, snapshots) = snapshots();
+        for old in &snapshots {
+            for new in snapshots.iter().filter(|new| size(new) >= size(old)) {
+                let proof = consistency_proof(&store, size(old), size(new)).unwrap();
+                verify_consistency_proof(&proof, old, new)
+                    .unwrap_or_else(|err| panic!("size {} -> {}: {err}", size(old), size(new)));
+            }
+        }
+    }
+
+    #[test]
+    fn proves_an_old_peak_kept_as_a_new_peak() {
+        let (store, snapshots) = snapshots();
+        // Peaks 7 and 10 of size 10 (6 leaves), peaks 7, 10 and 11 of size 11 (7 leaves)
+        let (old, new) = (&snapshots[5], &snapshots[6]);
+        let proof = consistency_proof(&store, 10, 11).unwrap();
+        assert!(proof.poseidon_paths.iter().all(Vec::is_empty));
+        assert!(proof.keccak_paths.iter().all(Vec::is_empty));
+        verify_consistency_proof(&proof, old, new).unwrap();
+    }
+
+    #[test]
+    fn rejects_a_tampered_sibling() {
+        let (store, snapshots) = snapshots();
+        // Peaks 7 and 10 of size 10 merge into 15 at size 15 (8 leaves)
+        let (old, new) = (&snapshots[5], &snapshots[7]);
+        let mut proof = consistency_proof(&store, 10, 15).unwrap();
+        proof.poseidon_paths[1][0] = Felt(Felt252::ONE);
+        assert!(matches!(
+            verify_consistency_proof(&proof, old, new),
+            Err(MmrError::PoseidonPeakMismatch { position: 15, .. })
+        ));
+
+        let mut proof = consistency_proof(&store, 10, 15).unwrap();
+        proof.keccak_paths[0][0] = b256_to_uint256(B256::ZERO);
+        assert!(matches!(
+            verify_consistency_proof(&proof, old, new),
+            Err(MmrError::KeccakPeakMismatch { position: 15, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_swapped_peaks() {
+        let (store, snapshots) = snapshots();
+        let (old, new) = (&snapshots[5], &snapshots[7]);
+        let proof = consistency_proof(&store, 10, 15).unwrap();
+
+        let mut swapped = old.clone();
+        swapped.poseidon_peaks.swap(0, 1);
+        swapped.keccak_peaks.swap(0, 1);
+        assert!(matches!(
+            verify_consistency_proof(&proof, &swapped, new),
+            Err(MmrError::PoseidonRootMismatch { .. })
+        ));
+
+        // Paths given in the wrong order hash the old peaks to other positions
+        let mut proof = proof;
+        proof.poseidon_paths.swap(0, 1);
+        assert!(verify_consistency_proof(&proof, old, new).is_err());
+    }
+
+    #[test]
+    fn rejects_a_shrinking_mmr() {
+        let (store, snapshots) = snapshots();
+        assert_eq!(
+            consistency_proof(&store, 11, 10).unwrap_err(),
+            MmrError::ConsistencyRange { old: 11, new: 10 }
+        );
+
+        let proof = consistency_proof(&store, 10, 11).unwrap();
+        assert_eq!(
+            verify_consistency_proof(&proof, &snapshots[6], &snapshots[5]).unwrap_err(),
+            MmrError::ConsistencyRange { old: 11, new: 10 }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_sizes() {
+        let (store, snapshots) = snapshots();
+        assert_eq!(
+            consistency_proof(&store, 2, 10).unwrap_err(),
+            MmrError::InvalidSize(2)
+        );
+        assert_eq!(
+            consistency_proof(&store, 1, 0).unwrap_err(),
+            MmrError::InvalidSize(0)
+        );
+
+        let proof = consistency_proof(&store, 10, 11).unwrap();
+        let mut new = snapshots[6].clone();
+        new.elements_count = Felt(Felt252::from(12));
+        assert_eq!(
+            verify_consistency_proof(&proof, &snapshots[5], &new).unwrap_err(),
+            MmrError::InvalidSize(12)
+        );
+    }
+}
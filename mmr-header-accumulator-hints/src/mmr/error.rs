@@ -0,0 +1,98 @@
+use alloy_primitives::B256;
+use cairo_vm_base::vm::cairo_vm::Felt252;
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum MmrError {
+    #[error("Value {0} does not fit in 128 bits")]
+    ValueOverflow(String),
+    #[error("Invalid MMR size: {0}")]
+    InvalidSize(u128),
+    /// The Poseidon and Keccak peak lists of a snapshot or proof don't both have one peak per
+    /// peak position
+    #[error("MMR of size {size} has {expected} peaks, got {poseidon} Poseidon and {keccak} Keccak peaks")]
+    PeakListsCountMismatch {
+        size: u128,
+        expected: usize,
+        poseidon: usize,
+        keccak: usize,
+    },
+    /// The start peaks given to a single hash [`Mmr`](crate::mmr::accumulator::Mmr) don't match
+    /// its size
+    #[error("MMR of size {size} has {expected} peaks, got {actual} start peaks")]
+    PeakCountMismatch {
+        size: u128,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Path of {path_len} nodes from height {height} goes above the tallest peak, at height {max_height}")]
+    PathTooLong {
+        height: u32,
+        path_len: usize,
+        max_height: u32,
+    },
+    #[error("Poseidon root mismatch: expected {expected:#x}, computed {computed:#x}")]
+    PoseidonRootMismatch {
+        expected: Felt252,
+        computed: Felt252,
+    },
+    #[error("Keccak root mismatch: expected {expected}, computed {computed}")]
+    KeccakRootMismatch { expected: B256, computed: B256 },
+    #[error("Missing MMR node at position {0}")]
+    MissingNode(u128),
+    #[error("Missing MMR leaf with index {0}")]
+    MissingLeaf(u128),
+    #[error("MMR size mismatch: expected {expected}, computed {computed}")]
+    SizeMismatch { expected: u128, computed: u128 },
+    #[error("Position {position} is outside of an MMR of size {size}")]
+    PositionOutOfRange { position: u128, size: u128 },
+    #[error("An MMR of {0} leaves exceeds the maximum MMR size")]
+    TooManyLeaves(u128),
+    #[error("Position {0} is not a leaf")]
+    NotALeaf(u128),
+    #[error("Position {0} is not a peak of the MMR")]
+    NotAPeak(u128),
+    #[error("Position {position} is not the last leaf of the MMR, expected {expected}")]
+    NotLastLeaf { position: u128, expected: u128 },
+    #[error("Proof declares a path of length {path_len}, got {poseidon} Poseidon and {keccak} Keccak siblings")]
+    PathLengthMismatch {
+        path_len: u128,
+        poseidon: usize,
+        keccak: usize,
+    },
+    #[error("Poseidon peak mismatch at position {position}: expected {expected:#x}, computed {computed:#x}")]
+    PoseidonPeakMismatch {
+        position: u128,
+        expected: Felt252,
+        computed: Felt252,
+    },
+    #[error(
+        "Keccak peak mismatch at position {position}: expected {expected}, computed {computed}"
+    )]
+    KeccakPeakMismatch {
+        position: u128,
+        expected: B256,
+        computed: B256,
+    },
+    #[error("Cannot prove consistency from size {old} to a smaller size {new}")]
+    ConsistencyRange { old: u128, new: u128 },
+    #[error("Proof is for sizes {proof_old} -> {proof_new}, snapshots have sizes {old} -> {new}")]
+    ConsistencySizeMismatch {
+        proof_old: u128,
+        proof_new: u128,
+        old: u128,
+        new: u128,
+    },
+    #[error("Old MMR has {expected} peaks, got {poseidon} Poseidon and {keccak} Keccak paths")]
+    ConsistencyPathsCountMismatch {
+        expected: usize,
+        poseidon: usize,
+        keccak: usize,
+    },
+    #[error("Store contains an MMR of size {expected}, got size {actual}")]
+    StoreSizeMismatch { expected: u128, actual: u128 },
+    #[error("Node store is empty, it has no last leaf to prove")]
+    EmptyStore,
+    #[error("Node store error: {0}")]
+    Storage(String),
+}
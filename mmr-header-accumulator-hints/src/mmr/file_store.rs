@@ -0,0 +1,484 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use alloy_primitives::B256;
+use cairo_vm_base::vm::cairo_vm::Felt252;
+use num_traits::ToPrimitive;
+
+use crate::beacon::ssz::hash_header_root;
+use crate::beacon::update::build_beacon_mmr_update_from_store;
+use crate::mmr::accumulator::MmrAccumulator;
+use crate::mmr::error::MmrError;
+use crate::mmr::store::NodeStore;
+use crate::mmr::utils::{compute_peaks_positions, is_valid_mmr_size};
+use crate::types::{
+    b256_to_uint256, uint256_to_b256, BeaconHeaderCairo, BeaconMmrUpdateCairo, MmrSnapshotCairo,
+};
+
+const NODES_FILE: &str = "nodes.bin";
+const LEAVES_FILE: &str = "leaves.bin";
+const HEAD_FILE: &str = "head";
+const HEAD_TMP_FILE: &str = "head.tmp";
+
+/// Poseidon node (32 bytes, big-endian) followed by the Keccak node (32 bytes).
+const NODE_RECORD_SIZE: u64 = 64;
+/// Header root (32 bytes) followed by the slot (8 bytes, big-endian).
+const LEAF_RECORD_SIZE: u64 = 40;
+
+/// A leaf of the MMR: the header root it was hashed from and the slot of that header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoredLeaf {
+    pub header_root: B256,
+    pub slot: u64,
+}
+
+/// Append-only, file-backed store of every node of both MMRs, plus an index of leaves.
+///
+/// Layout of the store directory:
+/// - `nodes.bin`: node records, the record at index `i` being the node at position `i + 1`.
+/// - `leaves.bin`: leaf records, the record at index `i` being the leaf with index `i`.
+/// - `head`: the committed number of nodes and leaves (two big-endian u128).
+///
+/// Records are appended first and `head` is atomically replaced afterwards, so a crash in the
+/// middle of an append leaves a tail of uncommitted records that is discarded on the next open
+/// or append.
+#[derive(Debug)]
+pub struct FileNodeStore {
+    dir: PathBuf,
+    nodes: File,
+    leaves: File,
+    elements_count: u128,
+    leaf_count: u128,
+}
+
+impl FileNodeStore {
+    /// Opens the store in `dir`, creating it if missing, and drops any uncommitted records.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, MmrError> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir).map_err(storage_error)?;
+
+        let (elements_count, leaf_count) = match std::fs::read(dir.join(HEAD_FILE)) {
+            Ok(head) => {
+                if head.len() != 32 {
+                    return Err(MmrError::Storage(format!(
+                        "Corrupted head file in {}",
+                        dir.display()
+                    )));
+                }
+                (
+                    u128::from_be_bytes(head[..16].try_into().unwrap()),
+                    u128::from_be_bytes(head[16..].try_into().unwrap()),
+                )
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => (0, 0),
+            Err(err) => return Err(storage_error(err)),
+        };
+
+        let nodes = open_records(&dir.join(NODES_FILE), elements_count, NODE_RECORD_SIZE)?;
+        let leaves = open_records(&dir.join(LEAVES_FILE), leaf_count, LEAF_RECORD_SIZE)?;
+
+        Ok(Self {
+            dir,
+            nodes,
+            leaves,
+            elements_count,
+            leaf_count,
+        })
+    }
+
+    /// Number of committed nodes, i.e. the current MMR size.
+    pub fn elements_count(&self) -> u128 {
+        self.elements_count
+    }
+
+    /// Number of committed leaves.
+    pub fn leaf_count(&self) -> u128 {
+        self.leaf_count
+    }
+
+    /// Returns the leaf with the given 0-based index.
+    pub fn leaf(&self, leaf_index: u128) -> Result<StoredLeaf, MmrError> {
+        if leaf_index >= self.leaf_count {
+            return Err(MmrError::MissingLeaf(leaf_index));
+        }
+        let mut record = [0u8; LEAF_RECORD_SIZE as usize];
+        read_record(&self.leaves, leaf_index, &mut record)?;
+        Ok(StoredLeaf {
+            header_root: B256::from_slice(&record[..32]),
+            slot: u64::from_be_bytes(record[32..].try_into().unwrap()),
+        })
+    }
+
+    /// Returns the last leaf of the MMR, if any.
+    pub fn last_leaf(&self) -> Result<Option<StoredLeaf>, MmrError> {
+        match self.leaf_count {
+            0 => Ok(None),
+            count => self.leaf(count - 1).map(Some),
+        }
+    }
+
+    /// Peaks of both MMRs, from left to right, at the historical size `mmr_size`.
+    pub fn peaks(&self, mmr_size: u128) -> Result<(Vec<Felt252>, Vec<B256>), MmrError> {
+        self.check_size(mmr_size)?;
+        compute_peaks_positions(mmr_size)
+            .into_iter()
+            .map(|pos| self.node(pos))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|peaks| peaks.into_iter().unzip())
+    }
+
+    /// Snapshot of the MMR at the historical size `mmr_size`.
+    pub fn snapshot(&self, mmr_size: u128) -> Result<MmrSnapshotCairo, MmrError> {
+        let (peaks_poseidon, peaks_keccak) = self.peaks(mmr_size)?;
+        Ok(MmrAccumulator::new(mmr_size, &peaks_poseidon, &peaks_keccak)?.snapshot())
+    }
+
+    /// Builds the update appending `headers` to the current state of the store, which must hold
+    /// at least one leaf.
+    pub fn build_update(
+        &self,
+        headers: &[BeaconHeaderCairo],
+    ) -> Result<BeaconMmrUpdateCairo, MmrError> {
+        let last_leaf = self.last_leaf()?.ok_or(MmrError::EmptyStore)?;
+        build_beacon_mmr_update_from_store(
+            self,
+            &self.snapshot(self.elements_count)?,
+            &b256_to_uint256(last_leaf.header_root),
+            headers,
+        )
+    }
+
+    /// Appends the headers of an update that was successfully run. The start snapshot must be the
+    /// current state of the store, and the appended nodes must produce the end snapshot.
+    ///
+    /// An empty store is first initialized with the single leaf MMR proven by the last leaf proof,
+    /// whose slot is unknown and recorded as 0.
+    pub fn append_update(&mut self, update: &BeaconMmrUpdateCairo) -> Result<(), MmrError> {
+        // Everything is checked before committing, so that a rejected update leaves the store as is.
+        let start_size = update.start_snapshot.elements_count()?;
+        let mut leaves = Vec::with_capacity(update.added_headers.len() + 1);
+        let mut accumulator = if self.elements_count == 0 && start_size == 1 {
+            let genesis = StoredLeaf {
+                header_root: uint256_to_b256(&update.last_leaf_proof.header_root),
+                slot: 0,
+            };
+            let mut accumulator = MmrAccumulator::empty();
+            accumulator.append_header_root(genesis.header_root);
+            check_same_snapshot(&accumulator.snapshot(), &update.start_snapshot)?;
+            leaves.push(genesis);
+            accumulator
+        } else {
+            if start_size != self.elements_count {
+                return Err(MmrError::StoreSizeMismatch {
+                    expected: self.elements_count,
+                    actual: start_size,
+                });
+            }
+            check_same_snapshot(&self.snapshot(start_size)?, &update.start_snapshot)?;
+            self.accumulator()?
+        };
+
+        for header in &update.added_headers {
+            let leaf = StoredLeaf {
+                header_root: hash_header_root(header),
+                slot: header
+                    .slot
+                    .0
+                    .to_u64()
+                    .ok_or_else(|| MmrError::ValueOverflow(format!("{:#x}", header.slot.0)))?,
+            };
+            accumulator.append_header_root(leaf.header_root);
+            leaves.push(leaf);
+        }
+        check_same_snapshot(&accumulator.snapshot(), &update.end_snapshot)?;
+        self.commit(&accumulator, &leaves)
+    }
+
+    /// Appends leaves to the store, starting a new MMR if the store is empty.
+    pub fn append_leaves(&mut self, leaves: &[StoredLeaf]) -> Result<(), MmrError> {
+        let mut accumulator = self.accumulator()?;
+        for leaf in leaves {
+            accumulator.append_header_root(leaf.header_root);
+        }
+        self.commit(&accumulator, leaves)
+    }
+
+    /// Accumulator starting from the current state of the store.
+    fn accumulator(&self) -> Result<MmrAccumulator, MmrError> {
+        if self.elements_count == 0 {
+            return Ok(MmrAccumulator::empty());
+        }
+        let (peaks_poseidon, peaks_keccak) = self.peaks(self.elements_count)?;
+        MmrAccumulator::new(self.elements_count, &peaks_poseidon, &peaks_keccak)
+    }
+
+    fn commit(
+        &mut self,
+        accumulator: &MmrAccumulator,
+        leaves: &[StoredLeaf],
+    ) -> Result<(), MmrError> {
+        let (poseidon_nodes, keccak_nodes) = accumulator.appended_nodes();
+        let mut node_records = Vec::with_capacity(poseidon_nodes.len() * NODE_RECORD_SIZE as usize);
+        for (poseidon, keccak) in poseidon_nodes.iter().zip(keccak_nodes) {
+            node_records.extend_from_slice(&poseidon.to_bytes_be());
+            node_records.extend_from_slice(keccak.as_slice());
+        }
+        let mut leaf_records = Vec::with_capacity(leaves.len() * LEAF_RECORD_SIZE as usize);
+        for leaf in leaves {
+            leaf_records.extend_from_slice(leaf.header_root.as_slice());
+            leaf_records.extend_from_slice(&leaf.slot.to_be_bytes());
+        }
+
+        // Drop the records of a previous append that failed before updating the head.
+        truncate_records(&self.nodes, self.elements_count, NODE_RECORD_SIZE)?;
+        truncate_records(&self.leaves, self.leaf_count, LEAF_RECORD_SIZE)?;
+        append_records(&mut self.nodes, &node_records)?;
+        append_records(&mut self.leaves, &leaf_records)?;
+
+        let elements_count = accumulator.elements_count();
+        let leaf_count = self.leaf_count + leaves.len() as u128;
+        let mut head = [0u8; 32];
+        head[..16].copy_from_slice(&elements_count.to_be_bytes());
+        head[16..].copy_from_slice(&leaf_count.to_be_bytes());
+
+        // Atomically replace the head so that the appended records become visible.
+        let tmp_path = self.dir.join(HEAD_TMP_FILE);
+        let mut tmp = File::create(&tmp_path).map_err(storage_error)?;
+        tmp.write_all(&head).map_err(storage_error)?;
+        tmp.sync_all().map_err(storage_error)?;
+        std::fs::rename(&tmp_path, self.dir.join(HEAD_FILE)).map_err(storage_error)?;
+        File::open(&self.dir)
+            .and_then(|dir| dir.sync_all())
+            .map_err(storage_error)?;
+
+        self.elements_count = elements_count;
+        self.leaf_count = leaf_count;
+        Ok(())
+    }
+
+    fn check_size(&self, mmr_size: u128) -> Result<(), MmrError> {
+        if !is_valid_mmr_size(mmr_size) {
+            return Err(MmrError::InvalidSize(mmr_size));
+        }
+        if mmr_size > self.elements_count {
+            return Err(MmrError::StoreSizeMismatch {
+                expected: self.elements_count,
+                actual: mmr_size,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl NodeStore for FileNodeStore {
+    fn get_node(&self, position: u128) -> Result<Option<(Felt252, B256)>, MmrError> {
+        if position == 0 || position > self.elements_count {
+            return Ok(None);
+        }
+        let mut record = [0u8; NODE_RECORD_SIZE as usize];
+        read_record(&self.nodes, position - 1, &mut record)?;
+        Ok(Some((
+            Felt252::from_bytes_be_slice(&record[..32]),
+            B256::from_slice(&record[32..]),
+        )))
+    }
+}
+
+fn check_same_snapshot(
+    stored: &MmrSnapshotCairo,
+    expected: &MmrSnapshotCairo,
+) -> Result<(), MmrError> {
+    let expected_size = expected.elements_count()?;
+    let stored_size = stored.elements_count()?;
+    if stored_size != expected_size {
+        return Err(MmrError::SizeMismatch {
+            expected: expected_size,
+            computed: stored_size,
+        });
+    }
+    if stored.poseidon_root.0 != expected.poseidon_root.0 {
+        return Err(MmrError::PoseidonRootMismatch {
+            expected: expected.poseidon_root.0,
+            computed: stored.poseidon_root.0,
+        });
+    }
+    let expected_keccak_root = uint256_to_b256(&expected.keccak_root);
+    let stored_keccak_root = uint256_to_b256(&stored.keccak_root);
+    if stored_keccak_root != expected_keccak_root {
+        return Err(MmrError::KeccakRootMismatch {
+            expected: expected_keccak_root,
+            computed: stored_keccak_root,
+        });
+    }
+    Ok(())
+}
+
+/// Opens a record file and truncates it to `count` committed records.
+fn open_records(path: &Path, count: u128, record_size: u64) -> Result<File, MmrError> {
+    let file = OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(path)
+        .map_err(storage_error)?;
+
+    let committed_len = (count as u64) * record_size;
+    let len = file.metadata().map_err(storage_error)?.len();
+    if len < committed_len {
+        return Err(MmrError::Storage(format!(
+            "{} is shorter than its committed length ({} < {})",
+            path.display(),
+            len,
+            committed_len
+        )));
+    }
+    if len > committed_len {
+        // Uncommitted records from an interrupted append.
+        truncate_records(&file, count, record_size)?;
+    }
+    Ok(file)
+}
+
+/// Truncates a record file to `count` committed records.
+fn truncate_records(file: &File, count: u128, record_size: u64) -> Result<(), MmrError> {
+    let committed_len = (count as u64) * record_size;
+    if file.metadata().map_err(storage_error)?.len() == committed_len {
+        return Ok(());
+    }
+    file.set_len(committed_len).map_err(storage_error)?;
+    file.sync_all().map_err(storage_error)
+}
+
+fn read_record(file: &File, index: u128, record: &mut [u8]) -> Result<(), MmrError> {
+    let mut file = file;
+    file.seek(SeekFrom::Start(index as u64 * record.len() as u64))
+        .map_err(storage_error)?;
+    file.read_exact(record).map_err(storage_error)
+}
+
+fn append_records(file: &mut File, records: &[u8]) -> Result<(), MmrError> {
+    file.write_all(records).map_err(storage_error)?;
+    file.sync_all().map_err(storage_error)
+}
+
+fn storage_error(err: std::io::Error) -> MmrError {
+    MmrError::Storage(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{example_input, temp_dir};
+
+    fn leaves(first_slot: u64, count: u64) -> Vec<StoredLeaf> {
+        (first_slot..first_slot + count)
+            .map(|slot| StoredLeaf {
+                header_root: B256::from(Felt252::from(slot).to_bytes_be()),
+                slot,
+            })
+            .collect()
+    }
+
+    fn file_len(dir: &Path, name: &str) -> u64 {
+        std::fs::metadata(dir.join(name)).unwrap().len()
+    }
+
+    #[test]
+    fn appends_again_after_a_failed_append() {
+        let dir = temp_dir("file_store_failed_append");
+        let mut store = FileNodeStore::open(&dir).unwrap();
+        store.append_leaves(&leaves(0, 3)).unwrap();
+        let committed = store.snapshot(store.elements_count()).unwrap();
+
+        // A read only handle makes the leaves append fail once the nodes are written.
+        let leaves_file = std::mem::replace(
+            &mut store.leaves,
+            File::open(dir.join(LEAVES_FILE)).unwrap(),
+        );
+        assert!(matches!(
+            store.append_leaves(&leaves(3, 2)),
+            Err(MmrError::Storage(_))
+        ));
+        assert_eq!(store.elements_count(), 4);
+        assert_eq!(store.leaf_count(), 3);
+        assert!(file_len(&dir, NODES_FILE) > 4 * NODE_RECORD_SIZE);
+        let snapshot = store.snapshot(4).unwrap();
+        assert_eq!(snapshot.poseidon_root.0, committed.poseidon_root.0);
+
+        store.leaves = leaves_file;
+        store.append_leaves(&leaves(3, 2)).unwrap();
+
+        let mut expected = FileNodeStore::open(temp_dir("file_store_expected")).unwrap();
+        expected.append_leaves(&leaves(0, 5)).unwrap();
+        assert_eq!(store.elements_count(), expected.elements_count());
+        assert_eq!(store.leaf_count(), 5);
+        assert_eq!(
+            file_len(&dir, NODES_FILE),
+            store.elements_count() as u64 * NODE_RECORD_SIZE
+        );
+        assert_eq!(file_len(&dir, LEAVES_FILE), 5 * LEAF_RECORD_SIZE);
+        assert_eq!(
+            store.peaks(store.elements_count()).unwrap(),
+            expected.peaks(expected.elements_count()).unwrap()
+        );
+        assert_eq!(store.leaf(4).unwrap(), leaves(4, 1)[0]);
+
+        let reopened = FileNodeStore::open(&dir).unwrap();
+        assert_eq!(reopened.elements_count(), store.elements_count());
+        assert_eq!(reopened.leaf_count(), 5);
+    }
+
+    #[test]
+    fn appends_the_example_update_to_an_empty_store() {
+        let input = example_input();
+        let mut store = FileNodeStore::open(temp_dir("file_store_example")).unwrap();
+        store.append_update(&input).unwrap();
+
+        assert_eq!(store.elements_count(), 64);
+        assert_eq!(store.leaf_count(), 33);
+        let snapshot = store.snapshot(64).unwrap();
+        assert_eq!(snapshot.poseidon_root.0, input.end_snapshot.poseidon_root.0);
+        assert_eq!(
+            uint256_to_b256(&snapshot.keccak_root),
+            uint256_to_b256(&input.end_snapshot.keccak_root)
+        );
+    }
+
+    #[test]
+    fn builds_updates_from_the_stored_leaves() {
+        let input = example_input();
+        let mut store = FileNodeStore::open(temp_dir("file_store_build_update")).unwrap();
+        assert_eq!(
+            store.build_update(&input.added_headers).unwrap_err(),
+            MmrError::EmptyStore
+        );
+
+        store
+            .append_leaves(&[StoredLeaf {
+                header_root: uint256_to_b256(&input.last_leaf_proof.header_root),
+                slot: 0,
+            }])
+            .unwrap();
+        let update = store.build_update(&input.added_headers).unwrap();
+        assert_eq!(
+            update.end_snapshot.poseidon_root.0,
+            input.end_snapshot.poseidon_root.0
+        );
+        assert_eq!(update.validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_an_update_without_committing_the_genesis_leaf() {
+        let mut input = example_input();
+        input.added_headers.pop();
+        let mut store = FileNodeStore::open(temp_dir("file_store_rejected")).unwrap();
+
+        assert!(matches!(
+            store.append_update(&input),
+            Err(MmrError::SizeMismatch { .. })
+        ));
+        assert_eq!(store.elements_count(), 0);
+        assert_eq!(store.leaf_count(), 0);
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt::Debug;
+
+use alloy_primitives::B256;
+use cairo_vm_base::vm::cairo_vm::Felt252;
+use starknet_crypto::poseidon_hash;
+use tiny_keccak::{Hasher, Keccak};
+
+/// Hash function used to build one of the MMRs.
+///
+/// The Cairo program maintains one MMR per hasher over the same leaves. The growth of a single
+/// [`Mmr`](crate::mmr::accumulator::Mmr) and the hashing of subtree paths are generic over this
+/// trait. The node stores, proofs, [`MmrAccumulator`](crate::mmr::accumulator::MmrAccumulator)
+/// and `verify_last_leaf` hold one Poseidon and one Keccak node per position, so a new hasher
+/// also has to be added to them.
+pub trait MmrHasher {
+    type Node: Copy + PartialEq + Debug;
+
+    /// Hashes a header root into a leaf.
+    fn hash_leaf(header_root: B256) -> Self::Node;
+
+    /// Hashes two children into their parent: H(left, right).
+    fn merge(left: Self::Node, right: Self::Node) -> Self::Node;
+
+    /// Computes the root from the MMR size and the bagged peaks: H(mmr_size, bagged_peaks).
+    fn root(mmr_size: u128, bagged_peaks: Self::Node) -> Self::Node;
+
+    /// Bags the peaks from left to right: H(peak1, H(peak2, H(peak3, ...))).
+    /// Returns `None` if there are no peaks.
+    fn bag_peaks(peaks: &[Self::Node]) -> Option<Self::Node> {
+        let (last, rest) = peaks.split_last()?;
+        Some(
+            rest.iter()
+                .rev()
+                .fold(*last, |acc, peak| Self::merge(*peak, acc)),
+        )
+    }
+
+    /// Computes the root of an MMR of size `mmr_size` from its peaks, from left to right.
+    fn root_from_peaks(mmr_size: u128, peaks: &[Self::Node]) -> Option<Self::Node> {
+        Some(Self::root(mmr_size, Self::bag_peaks(peaks)?))
+    }
+}
+
+/// Poseidon MMR over felts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PoseidonHasher;
+
+impl MmrHasher for PoseidonHasher {
+    type Node = Felt252;
+
+    /// `poseidon_uint256` in `leaf_hash.cairo`: Poseidon(low, high).
+    fn hash_leaf(header_root: B256) -> Felt252 {
+        let high = Felt252::from_bytes_be_slice(&header_root[..16]);
+        let low = Felt252::from_bytes_be_slice(&header_root[16..]);
+        poseidon_hash(low, high)
+    }
+
+    fn merge(left: Felt252, right: Felt252) -> Felt252 {
+        poseidon_hash(left, right)
+    }
+
+    fn root(mmr_size: u128, bagged_peaks: Felt252) -> Felt252 {
+        poseidon_hash(Felt252::from(mmr_size), bagged_peaks)
+    }
+}
+
+/// Keccak MMR over 32 bytes values, hashed in their big-endian encoding.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeccakHasher;
+
+impl MmrHasher for KeccakHasher {
+    type Node = B256;
+
+    /// `keccak_uint256` in `leaf_hash.cairo`: Keccak of the big-endian bytes.
+    fn hash_leaf(header_root: B256) -> B256 {
+        keccak(&[header_root.as_slice()])
+    }
+
+    fn merge(left: B256, right: B256) -> B256 {
+        keccak(&[left.as_slice(), right.as_slice()])
+    }
+
+    fn root(mmr_size: u128, bagged_peaks: B256) -> B256 {
+        let size = B256::left_padding_from(&mmr_size.to_be_bytes());
+        Self::merge(size, bagged_peaks)
+    }
+}
+
+fn keccak(inputs: &[&[u8]]) -> B256 {
+    let mut hasher = Keccak::v256();
+    for input in inputs {
+        hasher.update(input);
+    }
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    B256::from(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Nodes of `example_input.json`, as computed by the Cairo program. The start MMR holds the
+    // leaf of the zero header root, and the last peak of the end MMR is the leaf of its last
+    // header.
+    const ZERO_LEAF_POSEIDON: &str =
+        "0x0293d3e8a80f400daaaffdd5932e2bcc8814bab8f414a75dcacf87318f8b14c5";
+    const ZERO_LEAF_KECCAK: &str =
+        "0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563";
+    const START_ROOT_POSEIDON: &str =
+        "0x02853007a21aba6b2b3231616a1d76c9768330930f79c395a73aebac8b702a60";
+    const START_ROOT_KECCAK: &str =
+        "0xc09feb87461158077c30b36c837bdbb3f96c8b6ad7f1fdc55da01960a568b3d1";
+    const LAST_HEADER_ROOT: &str =
+        "0xa78a7ea0aea904bec2dca1541e3d9de038beb47386ef742f4c1d4773f5b132cb";
+    const END_PEAKS_POSEIDON: [&str; 2] = [
+        "0x07a17855bf21d8ffaab66b4c2ffa1d2f84954d2c5aead81c4bf9d18a5495a5c4",
+        "0x0655bc7e7950c699dcc6772886e88722feb1a0878091c935bbb14c90929261f2",
+    ];
+    const END_PEAKS_KECCAK: [&str; 2] = [
+        "0x914628a4e7466970040e47a9656aa4e53d0e112fdd04742b77fe04c7a996dbdc",
+        "0xedc74711372a1dfae2963f61943cc8a46f58353c5ed704be210fa9f03745a4c6",
+    ];
+    const END_ROOT_POSEIDON: &str =
+        "0x001ce5ddaf5e9fda59e3a2770fbedf638b345c203db2870012a5ce1246e76ddf";
+    const END_ROOT_KECCAK: &str =
+        "0xa936654985104d85173f8926139adac61e81f11cb9a017e8dda0187b803e8aef";
+
+    fn felt(hex: &str) -> Felt252 {
+        Felt252::from_hex(hex).unwrap()
+    }
+
+    fn b256(hex: &str) -> B256 {
+        hex.parse().unwrap()
+    }
+
+    #[test]
+    fn hashes_leaves_as_the_program() {
+        assert_eq!(
+            PoseidonHasher::hash_leaf(B256::ZERO),
+            felt(ZERO_LEAF_POSEIDON)
+        );
+        assert_eq!(KeccakHasher::hash_leaf(B256::ZERO), b256(ZERO_LEAF_KECCAK));
+
+        assert_eq!(
+            PoseidonHasher::hash_leaf(b256(LAST_HEADER_ROOT)),
+            felt(END_PEAKS_POSEIDON[1])
+        );
+        assert_eq!(
+            KeccakHasher::hash_leaf(b256(LAST_HEADER_ROOT)),
+            b256(END_PEAKS_KECCAK[1])
+        );
+    }
+
+    #[test]
+    fn merges_as_the_program() {
+        // The root of a single peak MMR is H(mmr_size, peak)
+        assert_eq!(
+            PoseidonHasher::merge(Felt252::ONE, felt(ZERO_LEAF_POSEIDON)),
+            felt(START_ROOT_POSEIDON)
+        );
+        assert_eq!(
+            KeccakHasher::merge(B256::left_padding_from(&[1]), b256(ZERO_LEAF_KECCAK)),
+            b256(START_ROOT_KECCAK)
+        );
+    }
+
+    #[test]
+    fn computes_roots_as_the_program() {
+        assert_eq!(
+            PoseidonHasher::root_from_peaks(1, &[felt(ZERO_LEAF_POSEIDON)]),
+            Some(felt(START_ROOT_POSEIDON))
+        );
+        assert_eq!(
+            KeccakHasher::root_from_peaks(1, &[b256(ZERO_LEAF_KECCAK)]),
+            Some(b256(START_ROOT_KECCAK))
+        );
+
+        assert_eq!(
+            PoseidonHasher::root_from_peaks(64, &END_PEAKS_POSEIDON.map(felt)),
+            Some(felt(END_ROOT_POSEIDON))
+        );
+        assert_eq!(
+            KeccakHasher::root_from_peaks(64, &END_PEAKS_KECCAK.map(b256)),
+            Some(b256(END_ROOT_KECCAK))
+        );
+        assert_eq!(PoseidonHasher::root_from_peaks(0, &[]), None);
+    }
+}
@@ -0,0 +1,9 @@
+pub mod accumulator;
+pub mod consistency;
+pub mod error;
+pub mod file_store;
+pub mod hash;
+pub mod proof;
+pub mod store;
+pub mod utils;
+pub mod verify;
@@ -0,0 +1,195 @@
+use alloy_primitives::B256;
+use cairo_vm_base::types::felt::Felt;
+use cairo_vm_base::vm::cairo_vm::Felt252;
+
+use crate::mmr::error::MmrError;
+use crate::mmr::store::NodeStore;
+use crate::mmr::utils::{
+    compute_height, compute_peaks_positions, is_valid_mmr_size, leaf_index_to_position,
+};
+use crate::types::{b256_to_uint256, InclusionProof};
+
+/// Collects the siblings from the node at `position` up to its peak in an MMR of size `mmr_size`,
+/// in the order consumed by `hash_subtree_path_poseidon` and `hash_subtree_path_keccak`.
+pub fn sibling_path<S: NodeStore>(
+    store: &S,
+    position: u128,
+    mmr_size: u128,
+) -> Result<(Vec<Felt252>, Vec<B256>), MmrError> {
+    if !is_valid_mmr_size(mmr_size) {
+        return Err(MmrError::InvalidSize(mmr_size));
+    }
+    if position == 0 || position > mmr_size {
+        return Err(MmrError::PositionOutOfRange {
+            position,
+            size: mmr_size,
+        });
+    }
+
+    let peaks_positions = compute_peaks_positions(mmr_size);
+    let mut poseidon_path = Vec::new();
+    let mut keccak_path = Vec::new();
+
+    let mut position = position;
+    let mut height = compute_height(position);
+    while !peaks_positions.contains(&position) {
+        let (sibling, parent) = if compute_height(position + 1) == height + 1 {
+            // Right child: parent at position + 1.
+            (position + 1 - (1u128 << (height + 1)), position + 1)
+        } else {
+            // Left child: parent at position + 2^(height+1).
+            (
+                position + (1u128 << (height + 1)) - 1,
+                position + (1u128 << (height + 1)),
+            )
+        };
+        let (poseidon, keccak) = store.node(sibling)?;
+        poseidon_path.push(poseidon);
+        keccak_path.push(keccak);
+
+        position = parent;
+        height += 1;
+    }
+
+    Ok((poseidon_path, keccak_path))
+}
+
+/// Position of the last leaf of an MMR of size `mmr_size`.
+pub fn last_leaf_position(mmr_size: u128) -> Result<u128, MmrError> {
+    if !is_valid_mmr_size(mmr_size) {
+        return Err(MmrError::InvalidSize(mmr_size));
+    }
+    let peaks_positions = compute_peaks_positions(mmr_size);
+    let last_peak_pos = peaks_positions[peaks_positions.len() - 1];
+    Ok(last_peak_pos - compute_height(last_peak_pos) as u128)
+}
+
+/// Builds the inclusion proof of the leaf at `position` in the MMR of size `mmr_size`.
+/// `mmr_size` may be any historical size of the stored MMR containing `position`.
+pub fn inclusion_proof<S: NodeStore>(
+    store: &S,
+    position: u128,
+    mmr_size: u128,
+) -> Result<InclusionProof, MmrError> {
+    if !is_valid_mmr_size(mmr_size) {
+        return Err(MmrError::InvalidSize(mmr_size));
+    }
+    if position == 0 || position > mmr_size {
+        return Err(MmrError::PositionOutOfRange {
+            position,
+            size: mmr_size,
+        });
+    }
+    if compute_height(position) != 0 {
+        return Err(MmrError::NotALeaf(position));
+    }
+
+    let (poseidon_path, keccak_path) = sibling_path(store, position, mmr_size)?;
+    let (poseidon_peaks, keccak_peaks): (Vec<Felt252>, Vec<B256>) =
+        compute_peaks_positions(mmr_size)
+            .into_iter()
+            .map(|pos| store.node(pos))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
+
+    Ok(InclusionProof {
+        header_position: Felt(Felt252::from(position)),
+        elements_count: Felt(Felt252::from(mmr_size)),
+        path_len: Felt(Felt252::from(poseidon_path.len())),
+        poseidon_path: poseidon_path.into_iter().map(Felt).collect(),
+        keccak_path: keccak_path.into_iter().map(b256_to_uint256).collect(),
+        poseidon_peaks: poseidon_peaks.into_iter().map(Felt).collect(),
+        keccak_peaks: keccak_peaks.into_iter().map(b256_to_uint256).collect(),
+    })
+}
+
+/// Builds the inclusion proof of the leaf with the given 0-based index.
+pub fn inclusion_proof_for_leaf<S: NodeStore>(
+    store: &S,
+    leaf_index: u128,
+    mmr_size: u128,
+) -> Result<InclusionProof, MmrError> {
+    inclusion_proof(store, leaf_index_to_position(leaf_index)?, mmr_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mmr::utils::leaf_count_to_mmr_size;
+    use crate::mmr::verify::verify_inclusion_proof;
+    use crate::test_utils::{header_root, mmr_with_leaves};
+
+    #[test]
+    fn proves_every_leaf() {
+        for leaf_count in [1, 3, 4, 7, 64] {
+            let (accumulator, store) = mmr_with_leaves(leaf_count);
+            let mmr_size = accumulator.elements_count();
+            assert_eq!(Ok(mmr_size), leaf_count_to_mmr_size(leaf_count as u128));
+            let (poseidon_root, keccak_root) = accumulator.roots();
+
+            for leaf_index in 0..leaf_count {
+                let proof = inclusion_proof_for_leaf(&store, leaf_index as u128, mmr_size).unwrap();
+                assert_eq!(
+                    proof.header_position.0,
+                    Felt252::from(leaf_index_to_position(leaf_index as u128).unwrap())
+                );
+                verify_inclusion_proof(
+                    &proof,
+                    &b256_to_uint256(header_root(leaf_index)),
+                    &Felt(poseidon_root),
+                    &b256_to_uint256(keccak_root),
+                )
+                .unwrap_or_else(|err| panic!("leaf {leaf_index} of {leaf_count}: {err}"));
+            }
+        }
+    }
+
+    #[test]
+    fn proves_leaves_at_a_historical_size() {
+        let (_, store) = mmr_with_leaves(7);
+        let (old, _) = mmr_with_leaves(4);
+        let (poseidon_root, keccak_root) = old.roots();
+
+        let proof = inclusion_proof_for_leaf(&store, 2, old.elements_count()).unwrap();
+        verify_inclusion_proof(
+            &proof,
+            &b256_to_uint256(header_root(2)),
+            &Felt(poseidon_root),
+            &b256_to_uint256(keccak_root),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_sizes_and_positions() {
+        let (_, store) = mmr_with_leaves(4);
+
+        assert_eq!(last_leaf_position(0), Err(MmrError::InvalidSize(0)));
+        assert_eq!(last_leaf_position(2), Err(MmrError::InvalidSize(2)));
+        assert_eq!(
+            sibling_path(&store, 1, 0).unwrap_err(),
+            MmrError::InvalidSize(0)
+        );
+        assert_eq!(
+            sibling_path(&store, 1, 5).unwrap_err(),
+            MmrError::InvalidSize(5)
+        );
+        assert_eq!(
+            inclusion_proof(&store, 8, 7).unwrap_err(),
+            MmrError::PositionOutOfRange {
+                position: 8,
+                size: 7
+            }
+        );
+        assert_eq!(
+            inclusion_proof(&store, 3, 7).unwrap_err(),
+            MmrError::NotALeaf(3)
+        );
+        // Nodes above the stored MMR are missing
+        assert_eq!(
+            inclusion_proof(&store, 1, 10).unwrap_err(),
+            MmrError::MissingNode(10)
+        );
+    }
+}
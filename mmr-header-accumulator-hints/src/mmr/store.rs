@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use alloy_primitives::B256;
+use cairo_vm_base::vm::cairo_vm::Felt252;
+
+use crate::mmr::error::MmrError;
+use crate::types::{felt_to_u128, uint256_to_b256, StoredMmrNode};
+
+/// Read access to the nodes of both MMRs by position.
+pub trait NodeStore {
+    /// Returns the Poseidon and Keccak nodes at `position`, if stored.
+    fn get_node(&self, position: u128) -> Result<Option<(Felt252, B256)>, MmrError>;
+
+    fn node(&self, position: u128) -> Result<(Felt252, B256), MmrError> {
+        self.get_node(position)?
+            .ok_or(MmrError::MissingNode(position))
+    }
+}
+
+/// In-memory node store.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryNodeStore {
+    nodes: HashMap<u128, (Felt252, B256)>,
+}
+
+impl MemoryNodeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, position: u128, poseidon: Felt252, keccak: B256) {
+        self.nodes.insert(position, (poseidon, keccak));
+    }
+
+    pub fn from_stored_nodes(nodes: &[StoredMmrNode]) -> Result<Self, MmrError> {
+        let mut store = Self::new();
+        for node in nodes {
+            store.insert(
+                felt_to_u128(&node.position)?,
+                node.poseidon.0,
+                uint256_to_b256(&node.keccak),
+            );
+        }
+        Ok(store)
+    }
+}
+
+impl NodeStore for MemoryNodeStore {
+    fn get_node(&self, position: u128) -> Result<Option<(Felt252, B256)>, MmrError> {
+        Ok(self.nodes.get(&position).copied())
+    }
+}
@@ -0,0 +1,307 @@
+//! Native counterparts of the position helpers in `src/mmr/utils.cairo`.
+//! Positions are 1-indexed:
+//! ```text
+//! H    MMR positions
+//! 2        7
+//!        /   \
+//! 1     3     6
+//!      / \   / \
+//! 0   1   2 4   5
+//! ```
+
+use crate::mmr::error::MmrError;
+
+/// Maximum MMR size accepted by `assert_mmr_size_is_valid`.
+pub const MAX_MMR_SIZE: u128 = 1 << 126;
+
+fn bit_length(x: u128) -> u32 {
+    u128::BITS - x.leading_zeros()
+}
+
+/// Returns whether `size` is a valid MMR size, i.e. 1 <= size <= 2^126 and the MMR
+/// decomposes into a list of balanced merkle trees of strictly decreasing heights.
+pub fn is_valid_mmr_size(size: u128) -> bool {
+    if size == 0 || size > MAX_MMR_SIZE {
+        return false;
+    }
+
+    let mut n = size;
+    let mut prev_peak = 0;
+    while n != 0 {
+        let i = bit_length(n);
+        let peak_tmp = (1u128 << i) - 1;
+        // Max (2^k - 1) value such that 2^(k-1) <= n
+        let peak = if n < peak_tmp {
+            (1u128 << (i - 1)) - 1
+        } else {
+            peak_tmp
+        };
+        if peak == prev_peak {
+            return false;
+        }
+        n -= peak;
+        prev_peak = peak;
+    }
+    true
+}
+
+/// Computes the height of the node at `position`, mirroring `compute_height_pre_alloc_pow2`.
+/// Assumes 1 <= position < 2^127.
+pub fn compute_height(position: u128) -> u32 {
+    debug_assert!(position != 0 && bit_length(position) < 128);
+
+    let mut x = position;
+    loop {
+        let bit_length = bit_length(x);
+        if x == (1u128 << bit_length) - 1 {
+            return bit_length - 1;
+        }
+        // Jump left on the MMR and continue until it's all ones.
+        x = x - (1u128 << (bit_length - 1)) + 1;
+    }
+}
+
+/// Computes the position of the leftmost peak of an MMR of size `mmr_len`.
+/// Assumes 1 <= mmr_len < 2^127.
+pub fn compute_first_peak_pos(mmr_len: u128) -> u128 {
+    let bit_length = bit_length(mmr_len);
+    let all_ones = (1u128 << bit_length) - 1;
+    if mmr_len == all_ones {
+        mmr_len
+    } else {
+        (1u128 << (bit_length - 1)) - 1
+    }
+}
+
+/// Returns the positions of the peaks from left to right for a valid MMR size.
+pub fn compute_peaks_positions(mmr_len: u128) -> Vec<u128> {
+    let mut peaks = vec![compute_first_peak_pos(mmr_len)];
+    let mut mmr_pos = peaks[0];
+    while mmr_pos != mmr_len {
+        let mut height = compute_height(mmr_pos);
+        // Right sibling has the same height as mmr_pos. Jump to its left child until inside the MMR.
+        let mut left_child = mmr_pos + (1u128 << (height + 1)) - 1;
+        while left_child > mmr_len {
+            left_child -= 1u128 << height;
+            height -= 1;
+        }
+        peaks.push(left_child);
+        mmr_pos = left_child;
+    }
+    peaks
+}
+
+/// Returns the MMR position of the leaf with the given 0-based index, which must fit in an MMR
+/// of at most [`MAX_MMR_SIZE`] elements.
+pub fn leaf_index_to_position(leaf_index: u128) -> Result<u128, MmrError> {
+    let leaf_count = leaf_index
+        .checked_add(1)
+        .ok_or(MmrError::TooManyLeaves(u128::MAX))?;
+    leaf_count_to_mmr_size(leaf_count)?;
+    Ok(leaf_count_to_mmr_size(leaf_index)? + 1)
+}
+
+/// Returns the 0-based leaf index of the leaf at `position`, at most [`MAX_MMR_SIZE`].
+pub fn position_to_leaf_index(position: u128) -> Result<u128, MmrError> {
+    if position > MAX_MMR_SIZE {
+        return Err(MmrError::PositionOutOfRange {
+            position,
+            size: MAX_MMR_SIZE,
+        });
+    }
+    if position == 0 || compute_height(position) != 0 {
+        return Err(MmrError::NotALeaf(position));
+    }
+    // The nodes before a leaf always form a valid MMR (or an empty one).
+    mmr_size_to_leaf_count(position - 1)
+}
+
+/// Returns the size of an MMR containing `leaf_count` leaves.
+/// Each leaf adds one node plus one parent per trailing one bit of the previous leaf count.
+/// The size must be at most [`MAX_MMR_SIZE`].
+pub fn leaf_count_to_mmr_size(leaf_count: u128) -> Result<u128, MmrError> {
+    if leaf_count > MAX_MMR_SIZE {
+        return Err(MmrError::TooManyLeaves(leaf_count));
+    }
+    let mmr_size = 2 * leaf_count - leaf_count.count_ones() as u128;
+    if mmr_size > MAX_MMR_SIZE {
+        return Err(MmrError::TooManyLeaves(leaf_count));
+    }
+    Ok(mmr_size)
+}
+
+/// Returns the number of leaves of an MMR of size `mmr_size`. A size of 0 is the empty MMR.
+pub fn mmr_size_to_leaf_count(mmr_size: u128) -> Result<u128, MmrError> {
+    if mmr_size == 0 {
+        return Ok(0);
+    }
+    if !is_valid_mmr_size(mmr_size) {
+        return Err(MmrError::InvalidSize(mmr_size));
+    }
+    // A peak of height h covers 2^h leaves.
+    Ok(compute_peaks_positions(mmr_size)
+        .into_iter()
+        .map(|pos| 1u128 << compute_height(pos))
+        .sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mmr::proof::last_leaf_position;
+
+    const N_LEAVES: u128 = 1024;
+
+    /// State of an MMR built naively, leaf by leaf, merging equal height peaks.
+    #[derive(Clone)]
+    struct NaiveMmr {
+        /// Height of the node at position `i + 1`
+        heights: Vec<u32>,
+        /// Positions and heights of the peaks, from left to right
+        peaks: Vec<(u128, u32)>,
+        /// Position of the last appended leaf
+        last_leaf: u128,
+    }
+
+    impl NaiveMmr {
+        fn new() -> Self {
+            Self {
+                heights: Vec::new(),
+                peaks: Vec::new(),
+                last_leaf: 0,
+            }
+        }
+
+        fn size(&self) -> u128 {
+            self.heights.len() as u128
+        }
+
+        fn append_leaf(&mut self) {
+            self.heights.push(0);
+            self.last_leaf = self.size();
+            self.peaks.push((self.size(), 0));
+            while let [.., (_, left), (_, right)] = self.peaks[..] {
+                if left != right {
+                    break;
+                }
+                self.peaks.truncate(self.peaks.len() - 2);
+                self.heights.push(left + 1);
+                self.peaks.push((self.size(), left + 1));
+            }
+        }
+    }
+
+    /// Naive MMR of every size reached while appending `N_LEAVES` leaves.
+    fn naive_mmrs() -> impl Iterator<Item = NaiveMmr> {
+        let mut mmr = NaiveMmr::new();
+        (0..N_LEAVES).map(move |_| {
+            mmr.append_leaf();
+            mmr.clone()
+        })
+    }
+
+    #[test]
+    fn heights_match_a_naive_build() {
+        let mmr = naive_mmrs().last().unwrap();
+        for (index, height) in mmr.heights.iter().enumerate() {
+            assert_eq!(
+                compute_height(index as u128 + 1),
+                *height,
+                "position {}",
+                index + 1
+            );
+        }
+    }
+
+    #[test]
+    fn peaks_positions_match_a_naive_build() {
+        let mut valid_sizes = Vec::new();
+        for mmr in naive_mmrs() {
+            let peaks: Vec<u128> = mmr.peaks.iter().map(|(pos, _)| *pos).collect();
+            assert_eq!(
+                compute_peaks_positions(mmr.size()),
+                peaks,
+                "size {}",
+                mmr.size()
+            );
+            assert_eq!(compute_first_peak_pos(mmr.size()), peaks[0]);
+            valid_sizes.push(mmr.size());
+        }
+
+        let max_size = *valid_sizes.last().unwrap();
+        for size in 0..=max_size {
+            assert_eq!(
+                is_valid_mmr_size(size),
+                valid_sizes.binary_search(&size).is_ok(),
+                "size {size}"
+            );
+        }
+    }
+
+    #[test]
+    fn last_leaf_matches_a_naive_build() {
+        for mmr in naive_mmrs() {
+            assert_eq!(
+                last_leaf_position(mmr.size()),
+                Ok(mmr.last_leaf),
+                "size {}",
+                mmr.size()
+            );
+        }
+    }
+
+    #[test]
+    fn leaf_indices_match_a_naive_build() {
+        for (leaf_index, mmr) in naive_mmrs().enumerate() {
+            let leaf_index = leaf_index as u128;
+            assert_eq!(leaf_index_to_position(leaf_index), Ok(mmr.last_leaf));
+            assert_eq!(position_to_leaf_index(mmr.last_leaf), Ok(leaf_index));
+            assert_eq!(leaf_count_to_mmr_size(leaf_index + 1), Ok(mmr.size()));
+            assert_eq!(mmr_size_to_leaf_count(mmr.size()), Ok(leaf_index + 1));
+        }
+
+        let mmr = naive_mmrs().last().unwrap();
+        for (index, height) in mmr.heights.iter().enumerate() {
+            let position = index as u128 + 1;
+            if *height != 0 {
+                assert_eq!(
+                    position_to_leaf_index(position),
+                    Err(MmrError::NotALeaf(position))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_leaves_beyond_the_maximum_size() {
+        // 2^125 + 1 leaves fill an MMR of size 2^126, with a last peak of a single leaf
+        let max_leaf_count = (1u128 << 125) + 1;
+        assert_eq!(leaf_count_to_mmr_size(max_leaf_count), Ok(MAX_MMR_SIZE));
+        assert_eq!(
+            leaf_count_to_mmr_size(max_leaf_count + 1),
+            Err(MmrError::TooManyLeaves(max_leaf_count + 1))
+        );
+        assert_eq!(
+            leaf_count_to_mmr_size(u128::MAX),
+            Err(MmrError::TooManyLeaves(u128::MAX))
+        );
+
+        assert_eq!(leaf_index_to_position(max_leaf_count - 1), Ok(MAX_MMR_SIZE));
+        assert_eq!(position_to_leaf_index(MAX_MMR_SIZE), Ok(max_leaf_count - 1));
+        for leaf_index in [max_leaf_count, 1 << 127, u128::MAX] {
+            assert!(matches!(
+                leaf_index_to_position(leaf_index),
+                Err(MmrError::TooManyLeaves(_))
+            ));
+        }
+        for position in [MAX_MMR_SIZE + 1, 1 << 127, u128::MAX] {
+            assert_eq!(
+                position_to_leaf_index(position),
+                Err(MmrError::PositionOutOfRange {
+                    position,
+                    size: MAX_MMR_SIZE,
+                })
+            );
+        }
+    }
+}
@@ -0,0 +1,444 @@
+use alloy_primitives::B256;
+use cairo_vm_base::types::{felt::Felt, uint256::Uint256};
+use cairo_vm_base::vm::cairo_vm::Felt252;
+
+use crate::mmr::error::MmrError;
+use crate::mmr::hash::{KeccakHasher, MmrHasher, PoseidonHasher};
+use crate::mmr::proof::last_leaf_position;
+use crate::mmr::utils::{
+    compute_first_peak_pos, compute_height, compute_peaks_positions, is_valid_mmr_size,
+};
+use crate::types::{
+    felt_to_u128, uint256_to_b256, InclusionProof, LastLeafProofCairo, MmrSnapshotCairo,
+};
+
+/// Hashes a subtree path from a node up to its peak, as `hash_subtree_path_poseidon` and
+/// `hash_subtree_path_keccak`. Returns the peak, its position and its height.
+///
+/// Orientation rule:
+/// - If height(pos+1) == height(pos) + 1, element is a right child: parent at pos + 1, H(sibling, element).
+/// - Else element is a left child: parent at pos + 2^(height+1), H(element, sibling).
+///
+/// A path reaching above the tallest peak of `mmr_size` cannot lead to one of its peaks, and is
+/// rejected before hashing so that the parent positions stay within 128 bits.
+pub fn hash_subtree_path<H: MmrHasher>(
+    element: H::Node,
+    height: u32,
+    position: u128,
+    inclusion_proof: &[H::Node],
+    mmr_size: u128,
+) -> Result<(H::Node, u128, u32), MmrError> {
+    if !is_valid_mmr_size(mmr_size) {
+        return Err(MmrError::InvalidSize(mmr_size));
+    }
+    let max_height = compute_height(compute_first_peak_pos(mmr_size));
+    if height as usize + inclusion_proof.len() > max_height as usize {
+        return Err(MmrError::PathTooLong {
+            height,
+            path_len: inclusion_proof.len(),
+            max_height,
+        });
+    }
+
+    Ok(inclusion_proof.iter().fold(
+        (element, position, height),
+        |(element, position, height), sibling| {
+            if compute_height(position + 1) == compute_height(position) + 1 {
+                (H::merge(*sibling, element), position + 1, height + 1)
+            } else {
+                (
+                    H::merge(element, *sibling),
+                    position + (1u128 << (height + 1)),
+                    height + 1,
+                )
+            }
+        },
+    ))
+}
+
+/// Natively performs the checks of `verify_last_leaf` against the start snapshot:
+/// - the proof paths hash up to the peaks stored in the snapshot at the computed peak positions,
+/// - the proven position is the last leaf of the MMR, as `assert_is_last_leaf_in_mmr`.
+pub fn verify_last_leaf(
+    proof: &LastLeafProofCairo,
+    snapshot: &MmrSnapshotCairo,
+) -> Result<(), MmrError> {
+    let peaks = snapshot.peaks_by_position()?;
+    let mmr_size = snapshot.elements_count()?;
+    let position = felt_to_u128(&proof.header_position)?;
+    check_path_lengths(&proof.path_len, &proof.poseidon_path, &proof.keccak_path)?;
+    if position == 0 || position > mmr_size {
+        return Err(MmrError::PositionOutOfRange {
+            position,
+            size: mmr_size,
+        });
+    }
+
+    let header_root = uint256_to_b256(&proof.header_root);
+    let poseidon_path: Vec<Felt252> = proof.poseidon_path.iter().map(|node| node.0).collect();
+    let keccak_path: Vec<B256> = proof.keccak_path.iter().map(uint256_to_b256).collect();
+
+    let (peak_poseidon, peak_poseidon_pos, _) = hash_subtree_path::<PoseidonHasher>(
+        PoseidonHasher::hash_leaf(header_root),
+        0,
+        position,
+        &poseidon_path,
+        mmr_size,
+    )?;
+    let (peak_keccak, peak_keccak_pos, _) = hash_subtree_path::<KeccakHasher>(
+        KeccakHasher::hash_leaf(header_root),
+        0,
+        position,
+        &keccak_path,
+        mmr_size,
+    )?;
+
+    let (_, expected_poseidon, _) = peaks
+        .iter()
+        .find(|(pos, _, _)| *pos == peak_poseidon_pos)
+        .ok_or(MmrError::NotAPeak(peak_poseidon_pos))?;
+    if *expected_poseidon != peak_poseidon {
+        return Err(MmrError::PoseidonPeakMismatch {
+            position: peak_poseidon_pos,
+            expected: *expected_poseidon,
+            computed: peak_poseidon,
+        });
+    }
+
+    let (_, _, expected_keccak) = peaks
+        .iter()
+        .find(|(pos, _, _)| *pos == peak_keccak_pos)
+        .ok_or(MmrError::NotAPeak(peak_keccak_pos))?;
+    if *expected_keccak != peak_keccak {
+        return Err(MmrError::KeccakPeakMismatch {
+            position: peak_keccak_pos,
+            expected: *expected_keccak,
+            computed: peak_keccak,
+        });
+    }
+
+    if compute_height(position) != 0 {
+        return Err(MmrError::NotALeaf(position));
+    }
+    let expected_position = last_leaf_position(mmr_size)?;
+    if position != expected_position {
+        return Err(MmrError::NotLastLeaf {
+            position,
+            expected: expected_position,
+        });
+    }
+
+    Ok(())
+}
+
+/// Verifies that `header_root` is included in the MMR committed to by `poseidon_root` and `keccak_root`.
+/// The proof peaks must hash to both roots, and the paths must hash to the peak containing the leaf.
+pub fn verify_inclusion_proof(
+    proof: &InclusionProof,
+    header_root: &Uint256,
+    poseidon_root: &Felt,
+    keccak_root: &Uint256,
+) -> Result<(), MmrError> {
+    let mmr_size = felt_to_u128(&proof.elements_count)?;
+    if !is_valid_mmr_size(mmr_size) {
+        return Err(MmrError::InvalidSize(mmr_size));
+    }
+    let peaks_positions = compute_peaks_positions(mmr_size);
+    if proof.poseidon_peaks.len() != peaks_positions.len()
+        || proof.keccak_peaks.len() != peaks_positions.len()
+    {
+        return Err(MmrError::PeakListsCountMismatch {
+            size: mmr_size,
+            expected: peaks_positions.len(),
+            poseidon: proof.poseidon_peaks.len(),
+            keccak: proof.keccak_peaks.len(),
+        });
+    }
+    check_path_lengths(&proof.path_len, &proof.poseidon_path, &proof.keccak_path)?;
+
+    let position = felt_to_u128(&proof.header_position)?;
+    if position == 0 || position > mmr_size {
+        return Err(MmrError::PositionOutOfRange {
+            position,
+            size: mmr_size,
+        });
+    }
+    if compute_height(position) != 0 {
+        return Err(MmrError::NotALeaf(position));
+    }
+
+    let peaks_poseidon: Vec<Felt252> = proof.poseidon_peaks.iter().map(|peak| peak.0).collect();
+    let peaks_keccak: Vec<B256> = proof.keccak_peaks.iter().map(uint256_to_b256).collect();
+
+    // Roots: H(mmr_size, bag_peaks)
+    let computed_poseidon_root =
+        PoseidonHasher::root_from_peaks(mmr_size, &peaks_poseidon).expect("MMR has no peaks");
+    if computed_poseidon_root != poseidon_root.0 {
+        return Err(MmrError::PoseidonRootMismatch {
+            expected: poseidon_root.0,
+            computed: computed_poseidon_root,
+        });
+    }
+    let computed_keccak_root =
+        KeccakHasher::root_from_peaks(mmr_size, &peaks_keccak).expect("MMR has no peaks");
+    let expected_keccak_root = uint256_to_b256(keccak_root);
+    if computed_keccak_root != expected_keccak_root {
+        return Err(MmrError::KeccakRootMismatch {
+            expected: expected_keccak_root,
+            computed: computed_keccak_root,
+        });
+    }
+
+    let header_root = uint256_to_b256(header_root);
+    let poseidon_path: Vec<Felt252> = proof.poseidon_path.iter().map(|node| node.0).collect();
+    let keccak_path: Vec<B256> = proof.keccak_path.iter().map(uint256_to_b256).collect();
+
+    let (peak_poseidon, peak_pos, _) = hash_subtree_path::<PoseidonHasher>(
+        PoseidonHasher::hash_leaf(header_root),
+        0,
+        position,
+        &poseidon_path,
+        mmr_size,
+    )?;
+    let peak_index = peaks_positions
+        .iter()
+        .position(|pos| *pos == peak_pos)
+        .ok_or(MmrError::NotAPeak(peak_pos))?;
+    if peaks_poseidon[peak_index] != peak_poseidon {
+        return Err(MmrError::PoseidonPeakMismatch {
+            position: peak_pos,
+            expected: peaks_poseidon[peak_index],
+            computed: peak_poseidon,
+        });
+    }
+
+    let (peak_keccak, _, _) = hash_subtree_path::<KeccakHasher>(
+        KeccakHasher::hash_leaf(header_root),
+        0,
+        position,
+        &keccak_path,
+        mmr_size,
+    )?;
+    if peaks_keccak[peak_index] != peak_keccak {
+        return Err(MmrError::KeccakPeakMismatch {
+            position: peak_pos,
+            expected: peaks_keccak[peak_index],
+            computed: peak_keccak,
+        });
+    }
+
+    Ok(())
+}
+
+fn check_path_lengths(
+    path_len: &Felt,
+    poseidon_path: &[Felt],
+    keccak_path: &[Uint256],
+) -> Result<(), MmrError> {
+    let path_len = felt_to_u128(path_len)?;
+    if path_len != poseidon_path.len() as u128 || path_len != keccak_path.len() as u128 {
+        return Err(MmrError::PathLengthMismatch {
+            path_len,
+            poseidon: poseidon_path.len(),
+            keccak: keccak_path.len(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beacon::update::build_last_leaf_proof;
+    use crate::mmr::proof::inclusion_proof_for_leaf;
+    use crate::mmr::store::MemoryNodeStore;
+    use crate::test_utils::{example_input, header_root, mmr_with_leaves};
+    use crate::types::b256_to_uint256;
+
+    /// MMR of 6 leaves, of size 10 with peaks at 7 and 10, along with its store and snapshot.
+    fn six_leaves() -> (MemoryNodeStore, MmrSnapshotCairo) {
+        let (accumulator, store) = mmr_with_leaves(6);
+        assert_eq!(accumulator.elements_count(), 10);
+        (store, accumulator.snapshot())
+    }
+
+    /// Inclusion proof of a leaf in the MMR of [`six_leaves`], shaped as a last leaf proof.
+    fn leaf_proof(store: &MemoryNodeStore, leaf_index: u64) -> LastLeafProofCairo {
+        let proof = inclusion_proof_for_leaf(store, leaf_index as u128, 10).unwrap();
+        LastLeafProofCairo {
+            header_root: b256_to_uint256(header_root(leaf_index)),
+            header_position: proof.header_position,
+            path_len: proof.path_len,
+            poseidon_path: proof.poseidon_path,
+            keccak_path: proof.keccak_path,
+        }
+    }
+
+    fn verify_inclusion(
+        proof: &InclusionProof,
+        leaf_index: u64,
+        snapshot: &MmrSnapshotCairo,
+    ) -> Result<(), MmrError> {
+        verify_inclusion_proof(
+            proof,
+            &b256_to_uint256(header_root(leaf_index)),
+            &snapshot.poseidon_root,
+            &snapshot.keccak_root,
+        )
+    }
+
+    #[test]
+    fn rejects_a_path_above_the_tallest_peak() {
+        // Peaks of size 64 are at heights 5 and 0.
+        let path = vec![Felt252::ONE; 6];
+        assert_eq!(
+            hash_subtree_path::<PoseidonHasher>(Felt252::ZERO, 0, 1, &path, 64).unwrap_err(),
+            MmrError::PathTooLong {
+                height: 0,
+                path_len: 6,
+                max_height: 5,
+            }
+        );
+        assert!(hash_subtree_path::<PoseidonHasher>(Felt252::ZERO, 0, 1, &path[..5], 64).is_ok());
+    }
+
+    #[test]
+    fn rejects_a_path_overflowing_the_positions() {
+        let path = vec![B256::ZERO; 127];
+        assert!(matches!(
+            hash_subtree_path::<KeccakHasher>(B256::ZERO, 0, 1, &path, 64),
+            Err(MmrError::PathTooLong { path_len: 127, .. })
+        ));
+    }
+
+    #[test]
+    fn verifies_the_example_last_leaf_proof() {
+        let input = example_input();
+        verify_last_leaf(&input.last_leaf_proof, &input.start_snapshot).unwrap();
+    }
+
+    #[test]
+    fn verifies_a_last_leaf_proof() {
+        let (store, snapshot) = six_leaves();
+        let proof = build_last_leaf_proof(&store, 10, &b256_to_uint256(header_root(5))).unwrap();
+        assert_eq!(proof.header_position.0, Felt252::from(9));
+        verify_last_leaf(&proof, &snapshot).unwrap();
+    }
+
+    #[test]
+    fn rejects_a_tampered_poseidon_path() {
+        let (store, snapshot) = six_leaves();
+        let mut proof = leaf_proof(&store, 5);
+        proof.poseidon_path[0] = Felt(Felt252::ONE);
+        assert!(matches!(
+            verify_last_leaf(&proof, &snapshot),
+            Err(MmrError::PoseidonPeakMismatch { position: 10, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_tampered_keccak_path() {
+        let (store, snapshot) = six_leaves();
+        let mut proof = leaf_proof(&store, 5);
+        proof.keccak_path[0] = b256_to_uint256(B256::ZERO);
+        assert!(matches!(
+            verify_last_leaf(&proof, &snapshot),
+            Err(MmrError::KeccakPeakMismatch { position: 10, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_path_not_ending_at_a_peak() {
+        let (store, snapshot) = six_leaves();
+        // Leaf 3 at position 5 is a right child, its first parent 6 is not a peak
+        let mut proof = leaf_proof(&store, 3);
+        proof.poseidon_path.truncate(1);
+        proof.keccak_path.truncate(1);
+        proof.path_len = Felt(Felt252::ONE);
+        assert_eq!(
+            verify_last_leaf(&proof, &snapshot),
+            Err(MmrError::NotAPeak(6))
+        );
+    }
+
+    #[test]
+    fn rejects_a_leaf_other_than_the_last() {
+        let (store, snapshot) = six_leaves();
+        assert_eq!(
+            verify_last_leaf(&leaf_proof(&store, 4), &snapshot),
+            Err(MmrError::NotLastLeaf {
+                position: 8,
+                expected: 9,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_a_path_length_mismatch() {
+        let (store, snapshot) = six_leaves();
+        let mut proof = leaf_proof(&store, 5);
+        proof.keccak_path.clear();
+        assert_eq!(
+            verify_last_leaf(&proof, &snapshot),
+            Err(MmrError::PathLengthMismatch {
+                path_len: 1,
+                poseidon: 1,
+                keccak: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn verifies_an_inclusion_proof() {
+        let (store, snapshot) = six_leaves();
+        for leaf_index in 0..6 {
+            let proof = inclusion_proof_for_leaf(&store, leaf_index as u128, 10).unwrap();
+            verify_inclusion(&proof, leaf_index, &snapshot).unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_a_tampered_inclusion_proof() {
+        let (store, snapshot) = six_leaves();
+        let proof = inclusion_proof_for_leaf(&store, 2, 10).unwrap();
+
+        // Another header root
+        assert!(matches!(
+            verify_inclusion(&proof, 1, &snapshot),
+            Err(MmrError::PoseidonPeakMismatch { position: 7, .. })
+        ));
+
+        let mut tampered = proof.clone();
+        tampered.keccak_path[1] = b256_to_uint256(B256::ZERO);
+        assert!(matches!(
+            verify_inclusion(&tampered, 2, &snapshot),
+            Err(MmrError::KeccakPeakMismatch { position: 7, .. })
+        ));
+
+        let mut tampered = proof.clone();
+        tampered.poseidon_peaks[1] = Felt(Felt252::ONE);
+        assert!(matches!(
+            verify_inclusion(&tampered, 2, &snapshot),
+            Err(MmrError::PoseidonRootMismatch { .. })
+        ));
+
+        let mut tampered = proof.clone();
+        tampered.header_position = Felt(Felt252::from(3));
+        assert_eq!(
+            verify_inclusion(&tampered, 2, &snapshot),
+            Err(MmrError::NotALeaf(3))
+        );
+
+        let mut tampered = proof;
+        tampered.poseidon_peaks.pop();
+        assert_eq!(
+            verify_inclusion(&tampered, 2, &snapshot),
+            Err(MmrError::PeakListsCountMismatch {
+                size: 10,
+                expected: 2,
+                poseidon: 1,
+                keccak: 2,
+            })
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use alloy_primitives::B256;
+use cairo_vm_base::vm::cairo_vm::{
+    air_public_input::PublicInput,
+    types::{builtin_name::BuiltinName, relocatable::MaybeRelocatable},
+    vm::runners::cairo_pie::CairoPie,
+    Felt252,
+};
+use num_traits::ToPrimitive;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum OutputError {
+    #[error("The output builtin segment was not found")]
+    MissingOutputSegment,
+    #[error("Expected {expected} output values, found {actual}")]
+    LengthMismatch { expected: usize, actual: usize },
+    #[error("Output value at offset {0} is missing")]
+    MissingValue(usize),
+    #[error("Output value at offset {0} is a relocatable")]
+    Relocatable(usize),
+    #[error("Output value at offset {offset} does not fit in {bits} bits")]
+    ValueOverflow { offset: usize, bits: u32 },
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+/// Public output of the program, mirroring `BeaconMmrUpdateOutput` in `src/beacon/types.cairo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MmrUpdateOutput {
+    pub start_keccak_root: B256,
+    pub start_poseidon_root: Felt252,
+    pub start_mmr_size: u128,
+    pub end_keccak_root: B256,
+    pub end_poseidon_root: Felt252,
+    pub end_mmr_size: u128,
+    pub first_slot: u64,
+    pub last_slot: u64,
+    pub last_header_root: B256,
+}
+
+impl MmrUpdateOutput {
+    /// Number of felts written to the output segment
+    pub const N_FIELDS: usize = 12;
+
+    /// Decodes the output from the values of the output segment.
+    pub fn from_felts(values: &[Felt252]) -> Result<Self, OutputError> {
+        if values.len() != Self::N_FIELDS {
+            return Err(OutputError::LengthMismatch {
+                expected: Self::N_FIELDS,
+                actual: values.len(),
+            });
+        }
+
+        Ok(Self {
+            start_keccak_root: read_uint256(values, 0)?,
+            start_poseidon_root: values[2],
+            start_mmr_size: read_u128(values, 3)?,
+            end_keccak_root: read_uint256(values, 4)?,
+            end_poseidon_root: values[6],
+            end_mmr_size: read_u128(values, 7)?,
+            first_slot: read_u64(values, 8)?,
+            last_slot: read_u64(values, 9)?,
+            last_header_root: read_uint256(values, 10)?,
+        })
+    }
+
+    /// Decodes the output from the output builtin segment of a PIE.
+    pub fn from_pie(pie: &CairoPie) -> Result<Self, OutputError> {
+        let segment = pie
+            .metadata
+            .builtin_segments
+            .get(&BuiltinName::output)
+            .ok_or(OutputError::MissingOutputSegment)?;
+
+        let mut cells = HashMap::new();
+        for ((segment_index, offset), value) in pie.memory.0.iter() {
+            if *segment_index as isize == segment.index {
+                cells.insert(*offset, value);
+            }
+        }
+
+        let values = (0..segment.size)
+            .map(|offset| match cells.get(&offset) {
+                Some(MaybeRelocatable::Int(value)) => Ok(*value),
+                Some(MaybeRelocatable::RelocatableValue(_)) => {
+                    Err(OutputError::Relocatable(offset))
+                }
+                None => Err(OutputError::MissingValue(offset)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_felts(&values)
+    }
+
+    /// Decodes the output from the public memory of an AIR public input, as written for the
+    /// Stwo and Stone provers.
+    pub fn from_public_input(public_input: &PublicInput) -> Result<Self, OutputError> {
+        let segment = public_input
+            .memory_segments
+            .get("output")
+            .ok_or(OutputError::MissingOutputSegment)?;
+
+        let cells: HashMap<usize, Option<Felt252>> = public_input
+            .public_memory
+            .iter()
+            .map(|entry| (entry.address, entry.value))
+            .collect();
+
+        let values = (segment.begin_addr..segment.stop_ptr)
+            .map(|address| {
+                let offset = address - segment.begin_addr;
+                cells
+                    .get(&address)
+                    .copied()
+                    .flatten()
+                    .ok_or(OutputError::MissingValue(offset))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_felts(&values)
+    }
+
+    /// Decodes the output from the JSON of an AIR public input.
+    pub fn from_public_input_json(json: &str) -> Result<Self, OutputError> {
+        let public_input: PublicInput = serde_json::from_str(json)?;
+        Self::from_public_input(&public_input)
+    }
+}
+
+fn read_u128(values: &[Felt252], offset: usize) -> Result<u128, OutputError> {
+    values[offset]
+        .to_u128()
+        .ok_or(OutputError::ValueOverflow { offset, bits: 128 })
+}
+
+fn read_u64(values: &[Felt252], offset: usize) -> Result<u64, OutputError> {
+    values[offset]
+        .to_u64()
+        .ok_or(OutputError::ValueOverflow { offset, bits: 64 })
+}
+
+/// Reads a Uint256 written as its low and high 128 bit limbs.
+fn read_uint256(values: &[Felt252], offset: usize) -> Result<B256, OutputError> {
+    let low = read_u128(values, offset)?;
+    let high = read_u128(values, offset + 1)?;
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(&high.to_be_bytes());
+    bytes[16..].copy_from_slice(&low.to_be_bytes());
+    Ok(B256::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const START_KECCAK_ROOT: &str =
+        "0x00112233445566778899aabbccddeeff0102030405060708090a0b0c0d0e0f10";
+    const END_KECCAK_ROOT: &str =
+        "0xffeeddccbbaa99887766554433221100f0e0d0c0b0a090807060504030201000";
+    const LAST_HEADER_ROOT: &str =
+        "0xa78a7ea0aea904bec2dca1541e3d9de038beb47386ef742f4c1d4773f5b132cb";
+
+    fn felt(hex: &str) -> Felt252 {
+        Felt252::from_hex(hex).unwrap()
+    }
+
+    /// Output segment values, with each Uint256 written low limb first.
+    fn output_values() -> Vec<Felt252> {
+        vec![
+            felt("0x0102030405060708090a0b0c0d0e0f10"),
+            felt("0x00112233445566778899aabbccddeeff"),
+            felt("0x1234"),
+            Felt252::from(1),
+            felt("0xf0e0d0c0b0a090807060504030201000"),
+            felt("0xffeeddccbbaa99887766554433221100"),
+            felt("0x5678"),
+            Felt252::from(64),
+            Felt252::from(7954432),
+            Felt252::from(7954463),
+            felt("0x38beb47386ef742f4c1d4773f5b132cb"),
+            felt("0xa78a7ea0aea904bec2dca1541e3d9de0"),
+        ]
+    }
+
+    fn expected_output() -> MmrUpdateOutput {
+        MmrUpdateOutput {
+            start_keccak_root: START_KECCAK_ROOT.parse().unwrap(),
+            start_poseidon_root: felt("0x1234"),
+            start_mmr_size: 1,
+            end_keccak_root: END_KECCAK_ROOT.parse().unwrap(),
+            end_poseidon_root: felt("0x5678"),
+            end_mmr_size: 64,
+            first_slot: 7954432,
+            last_slot: 7954463,
+            last_header_root: LAST_HEADER_ROOT.parse().unwrap(),
+        }
+    }
+
+    /// AIR public input JSON with the output segment at addresses 20 to 32.
+    fn public_input_json(values: &[Felt252]) -> String {
+        let public_memory: Vec<_> = values
+            .iter()
+            .enumerate()
+            .map(|(offset, value)| {
+                json!({"address": 20 + offset, "value": format!("0x{value:x}"), "page": 0})
+            })
+            .collect();
+        json!({
+            "layout": "all_cairo_stwo",
+            "rc_min": 0,
+            "rc_max": 100,
+            "n_steps": 1000,
+            "memory_segments": {
+                "program": {"begin_addr": 1, "stop_ptr": 5},
+                "execution": {"begin_addr": 5, "stop_ptr": 20},
+                "output": {"begin_addr": 20, "stop_ptr": 32},
+            },
+            "public_memory": public_memory,
+            "dynamic_params": null,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn decodes_the_output_values() {
+        assert_eq!(
+            MmrUpdateOutput::from_felts(&output_values()).unwrap(),
+            expected_output()
+        );
+    }
+
+    #[test]
+    fn rejects_output_values_of_another_length() {
+        let values = output_values();
+        assert!(matches!(
+            MmrUpdateOutput::from_felts(&values[..11]),
+            Err(OutputError::LengthMismatch {
+                expected: 12,
+                actual: 11
+            })
+        ));
+        assert!(matches!(
+            MmrUpdateOutput::from_felts(&[]),
+            Err(OutputError::LengthMismatch {
+                expected: 12,
+                actual: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_limbs_above_128_bits() {
+        let mut values = output_values();
+        values[5] = felt("0x100000000000000000000000000000000");
+        assert!(matches!(
+            MmrUpdateOutput::from_felts(&values),
+            Err(OutputError::ValueOverflow {
+                offset: 5,
+                bits: 128
+            })
+        ));
+    }
+
+    #[test]
+    fn decodes_a_public_input() {
+        let json = public_input_json(&output_values());
+        assert_eq!(
+            MmrUpdateOutput::from_public_input_json(&json).unwrap(),
+            expected_output()
+        );
+
+        // Public memory of the output segment only
+        let json = public_input_json(&output_values()[..11]);
+        assert!(matches!(
+            MmrUpdateOutput::from_public_input_json(&json),
+            Err(OutputError::MissingValue(11))
+        ));
+    }
+
+    #[test]
+    fn rejects_a_public_input_without_output() {
+        let mut json: serde_json::Value =
+            serde_json::from_str(&public_input_json(&output_values())).unwrap();
+        json["memory_segments"]
+            .as_object_mut()
+            .unwrap()
+            .remove("output");
+        assert!(matches!(
+            MmrUpdateOutput::from_public_input_json(&json.to_string()),
+            Err(OutputError::MissingOutputSegment)
+        ));
+    }
+}
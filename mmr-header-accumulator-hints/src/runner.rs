@@ -0,0 +1,531 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use cairo_vm_base::stwo_utils::FileWriter;
+use cairo_vm_base::vm::cairo_vm::{
+    cairo_run::{
+        self, cairo_run_program_with_initial_scope, write_encoded_memory, write_encoded_trace,
+    },
+    types::{
+        builtin_name::BuiltinName, exec_scope::ExecutionScopes, layout_name::LayoutName,
+        program::Program, relocatable::Relocatable,
+    },
+    vm::{
+        errors::{trace_errors::TraceError, vm_errors::VirtualMachineError},
+        runners::{
+            cairo_pie::CairoPie,
+            cairo_runner::{CairoRunner, ExecutionResources},
+        },
+    },
+    Felt252,
+};
+
+use crate::audit::audit_program_json;
+use crate::error::Error;
+use crate::failure::map_cairo_run_error;
+use crate::hint_processor::CustomHintProcessor;
+use crate::hints::debug::{DebugPrint, DEBUG_LOG, DEBUG_SILENCED};
+use crate::hints::result::BEACON_MMR_RESULT;
+use crate::layout::check_program_layouts;
+use crate::output::{MmrUpdateOutput, OutputError};
+use crate::summary::{PhaseTimings, RunSummary};
+use crate::types::{BeaconMmrUpdateCairo, MmrSnapshotCairo};
+
+/// Prover inputs written to the output directory after a proof mode run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProverInputs {
+    /// `memory.bin`, `trace.bin`, `air_public_inputs.json` and `air_private_inputs.json`
+    Stwo,
+    /// `memory.bin`, `trace.bin`, `air_public_input.json` and `air_private_input.json`
+    Stone,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunnerOptions {
+    pub layout: LayoutName,
+    pub proof_mode: bool,
+    /// Records and relocates the trace. Always enabled when prover inputs are written
+    pub trace_enabled: bool,
+    /// Directory the artifacts are written to, nothing is written when unset
+    pub output_dir: Option<PathBuf>,
+    /// File name of the PIE inside the output directory. PIEs are only built outside of proof mode
+    pub pie_name: Option<String>,
+    /// Prover inputs to write to the output directory, requires proof mode
+    pub prover_inputs: Option<ProverInputs>,
+    /// File name of the JSON run summary inside the output directory
+    pub summary_name: Option<String>,
+    /// Fails [`Runner::from_file`] if the program has hints the hint processor cannot execute
+    pub audit_hints: bool,
+    /// Skips the debug print hints instead of recording them in the outcome
+    pub silence_debug_prints: bool,
+}
+
+impl RunnerOptions {
+    /// Runs with the all_cairo layout and builds a PIE, written as `pie.zip` if an output
+    /// directory is set.
+    pub fn pie() -> Self {
+        Self {
+            layout: LayoutName::all_cairo,
+            proof_mode: false,
+            trace_enabled: false,
+            output_dir: None,
+            pie_name: Some("pie.zip".to_string()),
+            prover_inputs: None,
+            summary_name: Some("summary.json".to_string()),
+            audit_hints: true,
+            silence_debug_prints: false,
+        }
+    }
+
+    /// Runs in proof mode with the all_cairo_stwo layout and writes the Stwo prover inputs.
+    pub fn stwo(output_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            layout: LayoutName::all_cairo_stwo,
+            proof_mode: true,
+            trace_enabled: true,
+            output_dir: Some(output_dir.into()),
+            pie_name: None,
+            prover_inputs: Some(ProverInputs::Stwo),
+            summary_name: Some("summary.json".to_string()),
+            audit_hints: true,
+            silence_debug_prints: false,
+        }
+    }
+
+    /// Runs in proof mode with the given layout and writes the Stone prover inputs.
+    pub fn stone(layout: LayoutName, output_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            layout,
+            proof_mode: true,
+            trace_enabled: true,
+            output_dir: Some(output_dir.into()),
+            pie_name: None,
+            prover_inputs: Some(ProverInputs::Stone),
+            summary_name: Some("summary.json".to_string()),
+            audit_hints: true,
+            silence_debug_prints: false,
+        }
+    }
+
+    pub fn with_output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(output_dir.into());
+        self
+    }
+
+    pub fn with_pie_name(mut self, pie_name: impl Into<String>) -> Self {
+        self.pie_name = Some(pie_name.into());
+        self
+    }
+
+    pub fn with_summary_name(mut self, summary_name: impl Into<String>) -> Self {
+        self.summary_name = Some(summary_name.into());
+        self
+    }
+
+    pub fn with_hint_audit(mut self, audit_hints: bool) -> Self {
+        self.audit_hints = audit_hints;
+        self
+    }
+
+    pub fn with_silenced_debug_prints(mut self, silence_debug_prints: bool) -> Self {
+        self.silence_debug_prints = silence_debug_prints;
+        self
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        if self.prover_inputs.is_some() && !self.proof_mode {
+            return Err(Error::RunnerOptions(
+                "prover inputs can only be written in proof mode".to_string(),
+            ));
+        }
+        if self.prover_inputs.is_some() && self.output_dir.is_none() {
+            return Err(Error::RunnerOptions(
+                "prover inputs require an output directory".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for RunnerOptions {
+    fn default() -> Self {
+        Self::pie()
+    }
+}
+
+/// Paths of the files written by a run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunArtifacts {
+    pub pie: Option<PathBuf>,
+    pub memory: Option<PathBuf>,
+    pub trace: Option<PathBuf>,
+    pub air_public_input: Option<PathBuf>,
+    pub air_private_input: Option<PathBuf>,
+    pub summary: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+pub struct RunOutcome {
+    pub resources: ExecutionResources,
+    /// Built outside of proof mode only
+    pub pie: Option<CairoPie>,
+    /// Values written to the output builtin segment
+    pub outputs: Vec<Felt252>,
+    /// End snapshot computed by the program: the roots and size returned by
+    /// `run_beacon_mmr_update`, along with the full end peaks
+    pub end_snapshot: MmrSnapshotCairo,
+    /// Values printed by `src/debug` functions, in order. Empty when silenced
+    pub debug_log: Vec<DebugPrint>,
+    /// Resources, sizes, roots, program hash and timings, as written to the summary file
+    pub summary: RunSummary,
+    pub artifacts: RunArtifacts,
+}
+
+impl RunOutcome {
+    /// Decodes the values of the output segment.
+    pub fn mmr_update_output(&self) -> Result<MmrUpdateOutput, OutputError> {
+        MmrUpdateOutput::from_felts(&self.outputs)
+    }
+}
+
+/// Runs the compiled beacon MMR update program on inputs.
+#[derive(Debug)]
+pub struct Runner {
+    program: Program,
+    options: RunnerOptions,
+    /// Processor the hints are audited against and executed by
+    hint_processor: CustomHintProcessor,
+    /// Time taken by [`Runner::from_file`], reported in the run summary
+    load_time: Option<Duration>,
+}
+
+impl Runner {
+    /// Runner executing the hints with [`CustomHintProcessor::new`].
+    pub fn new(program: Program, options: RunnerOptions) -> Self {
+        Self {
+            program,
+            options,
+            hint_processor: CustomHintProcessor::new(),
+            load_time: None,
+        }
+    }
+
+    /// Replaces the hint processor, without auditing the program against it.
+    pub fn with_hint_processor(mut self, hint_processor: CustomHintProcessor) -> Self {
+        self.hint_processor = hint_processor;
+        self
+    }
+
+    /// Loads the compiled program from `path` and checks its layouts, see [`load_program`].
+    /// Unless disabled in the options, its hints are audited first.
+    pub fn from_file(path: &Path, options: RunnerOptions) -> Result<Self, Error> {
+        Self::from_file_with_hint_processor(path, options, CustomHintProcessor::new())
+    }
+
+    /// Same as [`Runner::from_file`], auditing the hints against `hint_processor`, which then
+    /// executes them.
+    pub fn from_file_with_hint_processor(
+        path: &Path,
+        options: RunnerOptions,
+        hint_processor: CustomHintProcessor,
+    ) -> Result<Self, Error> {
+        let load_start = Instant::now();
+        let program_file = read_program_file(path)?;
+        if options.audit_hints {
+            audit_program_json(&program_file, &hint_processor)?.ensure_handled()?;
+        }
+        let mut runner =
+            Self::new(parse_program(&program_file)?, options).with_hint_processor(hint_processor);
+        runner.load_time = Some(load_start.elapsed());
+        Ok(runner)
+    }
+
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    pub fn options(&self) -> &RunnerOptions {
+        &self.options
+    }
+
+    pub fn run(&mut self, input: BeaconMmrUpdateCairo) -> Result<RunOutcome, Error> {
+        self.options.check()?;
+
+        let trace_enabled = self.options.trace_enabled || self.options.prover_inputs.is_some();
+        let cairo_run_config = cairo_run::CairoRunConfig {
+            // Proof mode layouts must provide every builtin of the program
+            allow_missing_builtins: (!self.options.proof_mode).then_some(true),
+            layout: self.options.layout,
+            relocate_mem: trace_enabled,
+            trace_enabled,
+            proof_mode: self.options.proof_mode,
+            ..Default::default()
+        };
+
+        let mut exec_scopes = ExecutionScopes::new();
+        exec_scopes.insert_value("beacon_mmr_update", input.clone());
+        exec_scopes.insert_value(DEBUG_LOG, Vec::<DebugPrint>::new());
+        exec_scopes.insert_value(DEBUG_SILENCED, self.options.silence_debug_prints);
+
+        let run_start = Instant::now();
+        let cairo_runner = cairo_run_program_with_initial_scope(
+            &self.program,
+            &cairo_run_config,
+            &mut self.hint_processor,
+            exec_scopes,
+        )
+        .map_err(|err| map_cairo_run_error(&self.program, &input, err))?;
+        let run_time = run_start.elapsed();
+
+        let resources = cairo_runner.get_execution_resources()?;
+        let outputs = read_outputs(&cairo_runner)?;
+        let end_snapshot = cairo_runner
+            .exec_scopes
+            .get::<MmrSnapshotCairo>(BEACON_MMR_RESULT)?;
+        let debug_log = cairo_runner.exec_scopes.get::<Vec<DebugPrint>>(DEBUG_LOG)?;
+
+        let write_start = Instant::now();
+        let pie = if self.options.proof_mode {
+            None
+        } else {
+            Some(cairo_runner.get_cairo_pie()?)
+        };
+
+        let mut artifacts = RunArtifacts::default();
+        if let Some(output_dir) = &self.options.output_dir {
+            std::fs::create_dir_all(output_dir)?;
+            if let (Some(pie), Some(pie_name)) = (&pie, &self.options.pie_name) {
+                let pie_path = output_dir.join(pie_name);
+                pie.write_zip_file(&pie_path, true)?;
+                artifacts.pie = Some(pie_path);
+            }
+            match self.options.prover_inputs {
+                Some(ProverInputs::Stwo) => {
+                    generate_stwo_files(&cairo_runner, output_dir, &mut artifacts)?
+                }
+                Some(ProverInputs::Stone) => {
+                    generate_stone_files(&cairo_runner, output_dir, &mut artifacts)?
+                }
+                None => {}
+            }
+        }
+        let write_time = write_start.elapsed();
+
+        let summary = RunSummary::new(
+            &self.program,
+            &resources,
+            input.added_headers.len(),
+            &MmrUpdateOutput::from_felts(&outputs)?,
+            PhaseTimings::new(self.load_time, run_time, write_time),
+        )?;
+        if let (Some(output_dir), Some(summary_name)) =
+            (&self.options.output_dir, &self.options.summary_name)
+        {
+            let summary_path = output_dir.join(summary_name);
+            std::fs::write(&summary_path, serde_json::to_string_pretty(&summary)?)?;
+            artifacts.summary = Some(summary_path);
+        }
+
+        Ok(RunOutcome {
+            resources,
+            pie,
+            outputs,
+            end_snapshot,
+            debug_log,
+            summary,
+            artifacts,
+        })
+    }
+}
+
+/// Resolves the compiled program path against the working directory, failing if it doesn't exist.
+pub fn resolve_program_path(path: &Path) -> Result<PathBuf, Error> {
+    let resolved = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+    if !resolved.is_file() {
+        return Err(Error::ProgramNotFound(resolved));
+    }
+    Ok(resolved)
+}
+
+/// Loads the compiled program and checks that the input writers match its structs.
+pub fn load_program(path: &Path) -> Result<Program, Error> {
+    parse_program(&read_program_file(path)?)
+}
+
+fn read_program_file(path: &Path) -> Result<Vec<u8>, Error> {
+    let final_path = resolve_program_path(path)?;
+    std::fs::read(final_path).map_err(Error::IO)
+}
+
+fn parse_program(program_file: &[u8]) -> Result<Program, Error> {
+    let entrypoint = cairo_run::CairoRunConfig::default().entrypoint;
+    let program = Program::from_bytes(program_file, Some(entrypoint))?;
+    check_program_layouts(&program)?;
+    Ok(program)
+}
+
+fn read_outputs(cairo_runner: &CairoRunner) -> Result<Vec<Felt252>, Error> {
+    let Some(output_builtin) = cairo_runner
+        .vm
+        .builtin_runners
+        .iter()
+        .find(|builtin| builtin.name() == BuiltinName::output)
+    else {
+        return Ok(Vec::new());
+    };
+    let segment_index = output_builtin.base();
+    let size = cairo_runner
+        .vm
+        .segments
+        .get_segment_used_size(segment_index)
+        .unwrap_or_default();
+    let outputs = cairo_runner
+        .vm
+        .get_integer_range(Relocatable::from((segment_index as isize, 0)), size)
+        .map_err(VirtualMachineError::Memory)?;
+    Ok(outputs.into_iter().map(|value| *value).collect())
+}
+
+/// Writes the encoded `memory.bin` and `trace.bin` files.
+fn write_memory_and_trace(
+    cairo_runner: &CairoRunner,
+    output_dir: &Path,
+    artifacts: &mut RunArtifacts,
+) -> Result<(), Error> {
+    let memory_path = output_dir.join("memory.bin");
+    let memory_file = std::fs::File::create(&memory_path)?;
+    let mut memory_writer =
+        FileWriter::new(io::BufWriter::with_capacity(50 * 1024 * 1024, memory_file));
+    write_encoded_memory(&cairo_runner.relocated_memory, &mut memory_writer)?;
+    memory_writer.flush()?;
+    artifacts.memory = Some(memory_path);
+
+    let trace_path = output_dir.join("trace.bin");
+    let relocated_trace = cairo_runner
+        .relocated_trace
+        .as_ref()
+        .ok_or(Error::Trace(TraceError::TraceNotRelocated))?;
+    let trace_file = std::fs::File::create(&trace_path)?;
+    let mut trace_writer =
+        FileWriter::new(io::BufWriter::with_capacity(3 * 1024 * 1024, trace_file));
+    write_encoded_trace(relocated_trace, &mut trace_writer)?;
+    trace_writer.flush()?;
+    artifacts.trace = Some(trace_path);
+
+    Ok(())
+}
+
+fn generate_stwo_files(
+    cairo_runner: &CairoRunner,
+    output_dir: &Path,
+    artifacts: &mut RunArtifacts,
+) -> Result<(), Error> {
+    write_memory_and_trace(cairo_runner, output_dir, artifacts)?;
+
+    // 1. Generate air_public_inputs.json
+    let public_input = cairo_runner.get_air_public_input()?;
+    let public_input_json = serde_json::to_string_pretty(&public_input)?;
+    let public_input_path = output_dir.join("air_public_inputs.json");
+    std::fs::write(&public_input_path, public_input_json)?;
+    artifacts.air_public_input = Some(public_input_path);
+
+    // 2. Generate air_private_inputs.json (after binary files are created)
+    let private_input = cairo_runner.get_air_private_input();
+    let private_input_serializable =
+        private_input.to_serializable("trace.bin".to_string(), "memory.bin".to_string());
+    let private_input_json = serde_json::to_string_pretty(&private_input_serializable)?;
+    let private_input_path = output_dir.join("air_private_inputs.json");
+    std::fs::write(&private_input_path, private_input_json)?;
+    artifacts.air_private_input = Some(private_input_path);
+
+    Ok(())
+}
+
+fn generate_stone_files(
+    cairo_runner: &CairoRunner,
+    output_dir: &Path,
+    artifacts: &mut RunArtifacts,
+) -> Result<(), Error> {
+    write_memory_and_trace(cairo_runner, output_dir, artifacts)?;
+
+    let public_input = cairo_runner.get_air_public_input()?;
+    let public_input_json = serde_json::to_string_pretty(&public_input)?;
+    let public_input_path = output_dir.join("air_public_input.json");
+    std::fs::write(&public_input_path, public_input_json)?;
+    artifacts.air_public_input = Some(public_input_path);
+
+    // Stone opens the trace and memory files from the paths in the private input, as cairo-run writes them
+    let private_input = cairo_runner.get_air_private_input();
+    let path_string = |path: &Option<PathBuf>| {
+        path.as_deref()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let private_input_serializable = private_input.to_serializable(
+        path_string(&artifacts.trace),
+        path_string(&artifacts.memory),
+    );
+    let private_input_json = serde_json::to_string_pretty(&private_input_serializable)?;
+    let private_input_path = output_dir.join("air_private_input.json");
+    std::fs::write(&private_input_path, private_input_json)?;
+    artifacts.air_private_input = Some(private_input_path);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hint_processor::CustomHintProcessorBuilder;
+    use crate::hints::result::HINT_READ_BEACON_MMR_RESULT;
+    use crate::test_utils::{program_json, temp_dir};
+
+    #[test]
+    fn resolves_program_paths() {
+        // Tests run from the package directory
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        assert_eq!(
+            resolve_program_path(Path::new("Cargo.toml")).unwrap(),
+            std::env::current_dir().unwrap().join("Cargo.toml")
+        );
+        assert_eq!(resolve_program_path(&manifest).unwrap(), manifest);
+
+        let missing = temp_dir("runner_missing_program").join("main.json");
+        assert!(matches!(
+            resolve_program_path(&missing),
+            Err(Error::ProgramNotFound(path)) if path == missing
+        ));
+        assert!(matches!(
+            resolve_program_path(Path::new("missing/main.json")),
+            Err(Error::ProgramNotFound(path))
+                if path == std::env::current_dir().unwrap().join("missing/main.json")
+        ));
+    }
+
+    #[test]
+    fn audits_against_the_given_hint_processor() {
+        let dir = temp_dir("runner_audit");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("program.json");
+        std::fs::write(&path, program_json(&[(0, HINT_READ_BEACON_MMR_RESULT)])).unwrap();
+
+        let err = Runner::from_file_with_hint_processor(
+            &path,
+            RunnerOptions::pie(),
+            CustomHintProcessorBuilder::empty().build(),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, Error::UnhandledHints(codes) if codes == &[HINT_READ_BEACON_MMR_RESULT])
+        );
+
+        // The default processor handles the hint, loading then fails on the missing structs.
+        let err = Runner::from_file(&path, RunnerOptions::pie()).unwrap_err();
+        assert!(!matches!(err, Error::UnhandledHints(_)));
+    }
+}
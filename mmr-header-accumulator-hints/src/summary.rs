@@ -0,0 +1,156 @@
+//! Machine readable summary of a run, written next to the PIE to track the cost of each batch.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use cairo_vm_base::vm::cairo_vm::{
+    program_hash::compute_program_hash_chain, types::program::Program,
+    vm::runners::cairo_runner::ExecutionResources, Felt252,
+};
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::output::MmrUpdateOutput;
+
+/// Wall time of each phase of a run, in seconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PhaseTimings {
+    /// Loading and checking the program, unset when the runner was built from a loaded program
+    pub load_secs: Option<f64>,
+    /// Executing the program
+    pub run_secs: f64,
+    /// Building and writing the PIE and the prover inputs
+    pub write_secs: f64,
+}
+
+impl PhaseTimings {
+    pub fn new(load: Option<Duration>, run: Duration, write: Duration) -> Self {
+        Self {
+            load_secs: load.map(|load| load.as_secs_f64()),
+            run_secs: run.as_secs_f64(),
+            write_secs: write.as_secs_f64(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunSummary {
+    /// Pedersen hash chain of the program, as printed by `cairo-hash-program`
+    pub program_hash: String,
+    pub n_steps: usize,
+    pub n_memory_holes: usize,
+    /// Instances used by each builtin, such as `range_check`, `bitwise`, `keccak` and `poseidon`
+    pub builtin_instance_counter: BTreeMap<String, usize>,
+    pub n_headers: usize,
+    pub start_mmr_size: u128,
+    pub end_mmr_size: u128,
+    pub start_keccak_root: String,
+    pub start_poseidon_root: String,
+    pub end_keccak_root: String,
+    pub end_poseidon_root: String,
+    pub timings: PhaseTimings,
+}
+
+impl RunSummary {
+    pub fn new(
+        program: &Program,
+        resources: &ExecutionResources,
+        n_headers: usize,
+        output: &MmrUpdateOutput,
+        timings: PhaseTimings,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            program_hash: format!("{:#x}", program_hash(program)?),
+            n_steps: resources.n_steps,
+            n_memory_holes: resources.n_memory_holes,
+            builtin_instance_counter: resources
+                .builtin_instance_counter
+                .iter()
+                .map(|(builtin, count)| (builtin.to_str().to_string(), *count))
+                .collect(),
+            n_headers,
+            start_mmr_size: output.start_mmr_size,
+            end_mmr_size: output.end_mmr_size,
+            start_keccak_root: format!("{:#x}", output.start_keccak_root),
+            start_poseidon_root: format!("{:#x}", output.start_poseidon_root),
+            end_keccak_root: format!("{:#x}", output.end_keccak_root),
+            end_poseidon_root: format!("{:#x}", output.end_poseidon_root),
+            timings,
+        })
+    }
+}
+
+/// Hash of the program with the default bootloader version.
+pub fn program_hash(program: &Program) -> Result<Felt252, Error> {
+    Ok(compute_program_hash_chain(
+        &program.get_stripped_program()?,
+        0,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use alloy_primitives::B256;
+    use cairo_vm_base::vm::cairo_vm::types::builtin_name::BuiltinName;
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_utils::program_json;
+
+    fn program() -> Program {
+        let mut json: serde_json::Value = serde_json::from_slice(&program_json(&[])).unwrap();
+        json["identifiers"]["__main__.main"] = json!({"type": "function", "pc": 0});
+        Program::from_bytes(&serde_json::to_vec(&json).unwrap(), Some("main")).unwrap()
+    }
+
+    #[test]
+    fn serializes_the_summary() {
+        let program = program();
+        let resources = ExecutionResources {
+            n_steps: 1000,
+            n_memory_holes: 3,
+            builtin_instance_counter: HashMap::from([
+                (BuiltinName::range_check, 10),
+                (BuiltinName::poseidon, 4),
+            ]),
+        };
+        let output = MmrUpdateOutput {
+            start_keccak_root: B256::repeat_byte(0x11),
+            start_poseidon_root: Felt252::from(0x22),
+            start_mmr_size: 1,
+            end_keccak_root: B256::repeat_byte(0x33),
+            end_poseidon_root: Felt252::from(0x44),
+            end_mmr_size: 64,
+            first_slot: 7954432,
+            last_slot: 7954463,
+            last_header_root: B256::ZERO,
+        };
+        let timings = PhaseTimings::new(
+            None,
+            Duration::from_millis(1500),
+            Duration::from_millis(250),
+        );
+        let summary = RunSummary::new(&program, &resources, 32, &output, timings).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&summary).unwrap(),
+            json!({
+                "program_hash": format!("{:#x}", program_hash(&program).unwrap()),
+                "n_steps": 1000,
+                "n_memory_holes": 3,
+                "builtin_instance_counter": {"poseidon": 4, "range_check": 10},
+                "n_headers": 32,
+                "start_mmr_size": 1,
+                "end_mmr_size": 64,
+                "start_keccak_root": format!("0x{}", "11".repeat(32)),
+                "start_poseidon_root": "0x22",
+                "end_keccak_root": format!("0x{}", "33".repeat(32)),
+                "end_poseidon_root": "0x44",
+                "timings": {"load_secs": null, "run_secs": 1.5, "write_secs": 0.25},
+            })
+        );
+        assert!(summary.program_hash.starts_with("0x"));
+    }
+}
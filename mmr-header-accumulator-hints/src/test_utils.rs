@@ -0,0 +1,104 @@
+//! Fixtures shared by the unit tests.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use alloy_primitives::B256;
+use cairo_vm_base::vm::cairo_vm::{
+    hint_processor::hint_processor_definition::HintReference, types::relocatable::MaybeRelocatable,
+    vm::vm_core::VirtualMachine, Felt252,
+};
+use serde_json::json;
+
+use crate::mmr::accumulator::MmrAccumulator;
+use crate::mmr::store::MemoryNodeStore;
+use crate::types::BeaconMmrUpdateCairo;
+
+/// The update of `example_input.json`: 32 headers appended to the genesis MMR of size 1,
+/// growing it to size 64.
+pub fn example_input() -> BeaconMmrUpdateCairo {
+    serde_json::from_str(include_str!("../../example_input.json"))
+        .expect("example_input.json is a valid update")
+}
+
+/// Header root of the leaf with the given index in [`mmr_with_leaves`].
+pub fn header_root(leaf_index: u64) -> B256 {
+    B256::from(Felt252::from(leaf_index).to_bytes_be())
+}
+
+/// MMR of `leaf_count` leaves grown from an empty one, along with a store of all its nodes.
+pub fn mmr_with_leaves(leaf_count: u64) -> (MmrAccumulator, MemoryNodeStore) {
+    let mut accumulator = MmrAccumulator::empty();
+    for leaf_index in 0..leaf_count {
+        accumulator.append_header_root(header_root(leaf_index));
+    }
+    let mut store = MemoryNodeStore::new();
+    let (poseidon_nodes, keccak_nodes) = accumulator.appended_nodes();
+    for (index, (poseidon, keccak)) in poseidon_nodes.iter().zip(keccak_nodes).enumerate() {
+        store.insert(index as u128 + 1, *poseidon, *keccak);
+    }
+    (accumulator, store)
+}
+
+/// Empty directory named after `name` in the system temporary directory.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "mmr-header-accumulator-{name}-{}",
+        std::process::id()
+    ));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    dir
+}
+
+/// VM whose execution segment starts with `ids`, one cell each, along with references to them as
+/// hint variables.
+pub fn vm_with_ids(
+    ids: &[(&str, MaybeRelocatable)],
+) -> (VirtualMachine, HashMap<String, HintReference>) {
+    let mut vm = VirtualMachine::new(false);
+    vm.add_memory_segment();
+    let fp = vm.add_memory_segment();
+    vm.set_fp(0);
+
+    let mut ids_data = HashMap::new();
+    for (offset, (name, value)) in ids.iter().enumerate() {
+        vm.insert_value((fp + offset).unwrap(), value.clone())
+            .unwrap();
+        ids_data.insert(name.to_string(), HintReference::new_simple(offset as i32));
+    }
+    (vm, ids_data)
+}
+
+/// Compiled program JSON made of 3 `ret` instructions, with the given hints by pc.
+pub fn program_json(hints: &[(usize, &str)]) -> Vec<u8> {
+    let mut hints_by_pc = serde_json::Map::new();
+    for (pc, code) in hints {
+        let hint = json!({
+            "code": code,
+            "accessible_scopes": ["__main__"],
+            "flow_tracking_data": {
+                "ap_tracking": {"group": 0, "offset": 0},
+                "reference_ids": {},
+            },
+        });
+        hints_by_pc
+            .entry(pc.to_string())
+            .or_insert_with(|| json!([]))
+            .as_array_mut()
+            .unwrap()
+            .push(hint);
+    }
+    serde_json::to_vec(&json!({
+        "prime": "0x800000000000011000000000000000000000000000000000000000000000001",
+        "builtins": [],
+        "data": ["0x208b7fff7fff7ffe", "0x208b7fff7fff7ffe", "0x208b7fff7fff7ffe"],
+        "identifiers": {},
+        "hints": hints_by_pc,
+        "reference_manager": {"references": []},
+        "attributes": [],
+        "debug_info": null,
+    }))
+    .unwrap()
+}
@@ -1,7 +1,18 @@
+use alloy_primitives::B256;
 use cairo_vm_base::types::{felt::Felt, uint256::Uint256};
+use cairo_vm_base::vm::cairo_vm::Felt252;
+use mmr_header_accumulator_derive::{CairoReadable, CairoWritable};
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+use crate::mmr::error::MmrError;
+use crate::mmr::hash::{KeccakHasher, MmrHasher, PoseidonHasher};
+use crate::mmr::utils::{compute_peaks_positions, is_valid_mmr_size};
+
+pub mod readable;
+
+#[derive(Debug, Clone, Deserialize, Serialize, CairoWritable)]
 pub struct BeaconHeaderCairo {
     pub slot: Felt,
     pub proposer_index: Felt,
@@ -10,16 +21,19 @@ pub struct BeaconHeaderCairo {
     pub body_root: Uint256,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, CairoWritable, CairoReadable)]
+#[cairo(check = "check_peaks_len")]
 pub struct MmrSnapshotCairo {
     pub keccak_root: Uint256,
     pub poseidon_root: Felt,
     pub elements_count: Felt,
     pub keccak_peaks: Vec<Uint256>,
+    #[cairo(with_len = "peaks_len")]
     pub poseidon_peaks: Vec<Felt>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, CairoWritable)]
+#[cairo(check = "check_path_len")]
 pub struct LastLeafProofCairo {
     pub header_root: Uint256,
     pub header_position: Felt,
@@ -35,3 +49,285 @@ pub struct BeaconMmrUpdateCairo {
     pub added_headers: Vec<BeaconHeaderCairo>,
     pub last_leaf_proof: LastLeafProofCairo,
 }
+
+/// Proof that the leaf at `header_position` is included in an MMR of `elements_count` elements.
+/// The paths go from the leaf up to its peak, in the order consumed by `hash_subtree_path_poseidon`
+/// and `hash_subtree_path_keccak`. The peaks, from left to right, rebuild the root.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InclusionProof {
+    pub header_position: Felt,
+    pub elements_count: Felt,
+    pub path_len: Felt,
+    pub poseidon_path: Vec<Felt>,
+    pub keccak_path: Vec<Uint256>,
+    pub poseidon_peaks: Vec<Felt>,
+    pub keccak_peaks: Vec<Uint256>,
+}
+
+/// Proof that an MMR of `new_elements_count` elements extends an MMR of `old_elements_count` elements.
+/// For each old peak, from left to right, the paths go from that peak up to a peak of the new MMR.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ConsistencyProof {
+    pub old_elements_count: Felt,
+    pub new_elements_count: Felt,
+    pub poseidon_paths: Vec<Vec<Felt>>,
+    pub keccak_paths: Vec<Vec<Uint256>>,
+}
+
+/// A node of both MMRs at a given position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredMmrNode {
+    pub position: Felt,
+    pub poseidon: Felt,
+    pub keccak: Uint256,
+}
+
+/// State of the MMR before an update: its snapshot, the header root of its last leaf and
+/// the stored nodes needed to prove that leaf.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MmrStartState {
+    pub snapshot: MmrSnapshotCairo,
+    pub last_header_root: Uint256,
+    pub nodes: Vec<StoredMmrNode>,
+}
+
+impl MmrSnapshotCairo {
+    /// Returns the number of elements in the MMR as a native integer.
+    pub fn elements_count(&self) -> Result<u128, MmrError> {
+        felt_to_u128(&self.elements_count)
+    }
+
+    /// Returns the peaks from left to right along with their positions, after checking that
+    /// the size is valid and that both peak lists match the number of peaks for that size.
+    pub fn peaks_by_position(&self) -> Result<Vec<(u128, Felt252, B256)>, MmrError> {
+        let elements_count = self.elements_count()?;
+        if !is_valid_mmr_size(elements_count) {
+            return Err(MmrError::InvalidSize(elements_count));
+        }
+
+        let peaks_positions = compute_peaks_positions(elements_count);
+        if self.poseidon_peaks.len() != peaks_positions.len()
+            || self.keccak_peaks.len() != peaks_positions.len()
+        {
+            return Err(MmrError::PeakListsCountMismatch {
+                size: elements_count,
+                expected: peaks_positions.len(),
+                poseidon: self.poseidon_peaks.len(),
+                keccak: self.keccak_peaks.len(),
+            });
+        }
+
+        Ok(peaks_positions
+            .into_iter()
+            .zip(self.poseidon_peaks.iter().zip(&self.keccak_peaks))
+            .map(|(pos, (poseidon, keccak))| (pos, poseidon.0, uint256_to_b256(keccak)))
+            .collect())
+    }
+
+    /// Checks that both roots match H(elements_count, bag_peaks), as `initialize_peaks` does.
+    pub fn check_roots(&self) -> Result<(), MmrError> {
+        let elements_count = self.elements_count()?;
+        let (peaks_poseidon, peaks_keccak): (Vec<Felt252>, Vec<B256>) = self
+            .peaks_by_position()?
+            .into_iter()
+            .map(|(_, poseidon, keccak)| (poseidon, keccak))
+            .unzip();
+
+        let poseidon_root = PoseidonHasher::root_from_peaks(elements_count, &peaks_poseidon)
+            .expect("MMR has no peaks");
+        if poseidon_root != self.poseidon_root.0 {
+            return Err(MmrError::PoseidonRootMismatch {
+                expected: self.poseidon_root.0,
+                computed: poseidon_root,
+            });
+        }
+
+        let keccak_root =
+            KeccakHasher::root_from_peaks(elements_count, &peaks_keccak).expect("MMR has no peaks");
+        let expected_keccak_root = uint256_to_b256(&self.keccak_root);
+        if keccak_root != expected_keccak_root {
+            return Err(MmrError::KeccakRootMismatch {
+                expected: expected_keccak_root,
+                computed: keccak_root,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+pub fn felt_to_u128(value: &Felt) -> Result<u128, MmrError> {
+    value
+        .0
+        .to_u128()
+        .ok_or_else(|| MmrError::ValueOverflow(format!("{:#x}", value.0)))
+}
+
+pub fn uint256_to_b256(value: &Uint256) -> B256 {
+    B256::left_padding_from(&value.0.to_bytes_be())
+}
+
+pub fn b256_to_uint256(value: B256) -> Uint256 {
+    Uint256(BigUint::from_bytes_be(value.as_slice()))
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_vm_base::cairo_type::CairoWritable;
+    use cairo_vm_base::vm::cairo_vm::{
+        types::relocatable::{MaybeRelocatable, Relocatable},
+        vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
+    };
+
+    use super::*;
+
+    fn felt(value: u64) -> Felt {
+        Felt(Felt252::from(value))
+    }
+
+    fn uint256(low: u64, high: u64) -> Uint256 {
+        Uint256((BigUint::from(high) << 128) + BigUint::from(low))
+    }
+
+    fn int(value: u64) -> Option<MaybeRelocatable> {
+        Some(MaybeRelocatable::Int(Felt252::from(value)))
+    }
+
+    fn ptr(segment_index: isize) -> Option<MaybeRelocatable> {
+        Some(MaybeRelocatable::RelocatableValue(Relocatable::from((
+            segment_index,
+            0,
+        ))))
+    }
+
+    /// Writes `value` at the start of a new segment and returns the end address.
+    fn write(vm: &mut VirtualMachine, value: &impl CairoWritable) -> Result<usize, HintError> {
+        let address = vm.add_memory_segment();
+        Ok(value.to_memory(vm, address)?.offset)
+    }
+
+    /// The cells of a segment, up to one past `len` to catch extra writes.
+    fn cells(
+        vm: &VirtualMachine,
+        segment_index: isize,
+        len: usize,
+    ) -> Vec<Option<MaybeRelocatable>> {
+        (0..=len)
+            .map(|offset| vm.get_maybe(&Relocatable::from((segment_index, offset))))
+            .collect()
+    }
+
+    #[test]
+    fn writes_a_header() {
+        let header = BeaconHeaderCairo {
+            slot: felt(1),
+            proposer_index: felt(2),
+            parent_root: uint256(3, 4),
+            state_root: uint256(5, 6),
+            body_root: uint256(7, 8),
+        };
+        let mut vm = VirtualMachine::new(false);
+
+        assert_eq!(write(&mut vm, &header).unwrap(), 8);
+        assert_eq!(BeaconHeaderCairo::n_fields(), 8);
+        assert_eq!(
+            cells(&vm, 0, 8),
+            [
+                int(1),
+                int(2),
+                int(3),
+                int(4),
+                int(5),
+                int(6),
+                int(7),
+                int(8),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_a_snapshot() {
+        let snapshot = MmrSnapshotCairo {
+            keccak_root: uint256(1, 2),
+            poseidon_root: felt(3),
+            elements_count: felt(4),
+            keccak_peaks: vec![uint256(5, 6), uint256(7, 8)],
+            poseidon_peaks: vec![felt(9), felt(10)],
+        };
+        let mut vm = VirtualMachine::new(false);
+
+        assert_eq!(write(&mut vm, &snapshot).unwrap(), 7);
+        assert_eq!(MmrSnapshotCairo::n_fields(), 7);
+        assert_eq!(
+            cells(&vm, 0, 7),
+            [int(1), int(2), int(3), int(4), ptr(1), ptr(2), int(2), None]
+        );
+        assert_eq!(cells(&vm, 1, 4), [int(5), int(6), int(7), int(8), None]);
+        assert_eq!(cells(&vm, 2, 2), [int(9), int(10), None]);
+        assert_eq!(
+            MmrSnapshotCairo::cairo_members(),
+            [
+                ("keccak_root", 2),
+                ("poseidon_root", 1),
+                ("elements_count", 1),
+                ("keccak_peaks", 1),
+                ("poseidon_peaks", 1),
+                ("peaks_len", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_a_last_leaf_proof() {
+        let proof = LastLeafProofCairo {
+            header_root: uint256(1, 2),
+            header_position: felt(3),
+            path_len: felt(1),
+            poseidon_path: vec![felt(4)],
+            keccak_path: vec![uint256(5, 6)],
+        };
+        let mut vm = VirtualMachine::new(false);
+
+        assert_eq!(write(&mut vm, &proof).unwrap(), 6);
+        assert_eq!(LastLeafProofCairo::n_fields(), 6);
+        assert_eq!(
+            cells(&vm, 0, 6),
+            [int(1), int(2), int(3), int(1), ptr(1), ptr(2), None]
+        );
+        assert_eq!(cells(&vm, 1, 1), [int(4), None]);
+        assert_eq!(cells(&vm, 2, 2), [int(5), int(6), None]);
+    }
+
+    #[test]
+    fn checks_before_writing() {
+        let snapshot = MmrSnapshotCairo {
+            keccak_root: uint256(1, 2),
+            poseidon_root: felt(3),
+            elements_count: felt(1),
+            keccak_peaks: vec![uint256(5, 6)],
+            poseidon_peaks: vec![],
+        };
+        let mut vm = VirtualMachine::new(false);
+        assert!(matches!(
+            write(&mut vm, &snapshot),
+            Err(HintError::CustomHint(_))
+        ));
+        assert_eq!(cells(&vm, 0, 0), [None]);
+        assert_eq!(vm.segments.num_segments(), 1);
+
+        let proof = LastLeafProofCairo {
+            header_root: uint256(1, 2),
+            header_position: felt(3),
+            path_len: felt(1),
+            poseidon_path: vec![felt(4)],
+            keccak_path: vec![],
+        };
+        let mut vm = VirtualMachine::new(false);
+        assert!(matches!(
+            write(&mut vm, &proof),
+            Err(HintError::CustomHint(_))
+        ));
+        assert_eq!(cells(&vm, 0, 0), [None]);
+    }
+}
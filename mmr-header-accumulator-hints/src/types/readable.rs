@@ -0,0 +1,144 @@
+//! Reads values computed by the Cairo program back from the VM memory.
+//!
+//! `CairoReadable` is the counterpart of `CairoWritable`: `from_memory` reads the `n_fields()`
+//! fields starting at `address`, in the member order of the Cairo struct.
+//!
+//! Structs derive it with `mmr_header_accumulator_derive::CairoReadable`, which reads the layout
+//! written by the `CairoWritable` derive.
+
+use cairo_vm_base::cairo_type::CairoType;
+use cairo_vm_base::types::{felt::Felt, uint256::Uint256};
+use cairo_vm_base::vm::cairo_vm::{
+    types::relocatable::Relocatable,
+    vm::{errors::hint_errors::HintError, vm_core::VirtualMachine},
+};
+use num_traits::ToPrimitive;
+
+pub trait CairoReadable: Sized {
+    fn from_memory(vm: &VirtualMachine, address: Relocatable) -> Result<Self, HintError>;
+
+    fn n_fields() -> usize;
+}
+
+impl CairoReadable for Felt {
+    fn from_memory(vm: &VirtualMachine, address: Relocatable) -> Result<Self, HintError> {
+        <Felt as CairoType>::from_memory(vm, address)
+    }
+
+    fn n_fields() -> usize {
+        <Felt as CairoType>::n_fields()
+    }
+}
+
+impl CairoReadable for Uint256 {
+    fn from_memory(vm: &VirtualMachine, address: Relocatable) -> Result<Self, HintError> {
+        <Uint256 as CairoType>::from_memory(vm, address)
+    }
+
+    fn n_fields() -> usize {
+        <Uint256 as CairoType>::n_fields()
+    }
+}
+
+/// Reads `len` consecutive elements starting at `ptr`, such as a `felt*` or `Uint256*` array.
+pub fn read_array<T: CairoReadable>(
+    vm: &VirtualMachine,
+    ptr: Relocatable,
+    len: usize,
+) -> Result<Vec<T>, HintError> {
+    (0..len)
+        .map(|index| T::from_memory(vm, (ptr + index * T::n_fields())?))
+        .collect()
+}
+
+/// Reads an array length, which must fit in a `usize`.
+pub fn read_len(vm: &VirtualMachine, address: Relocatable) -> Result<usize, HintError> {
+    let len = vm.get_integer(address)?;
+    len.to_usize().ok_or(HintError::BigintToUsizeFail)
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_vm_base::cairo_type::CairoWritable;
+    use cairo_vm_base::vm::cairo_vm::Felt252;
+    use mmr_header_accumulator_derive::CairoWritable;
+    use num_bigint::BigUint;
+
+    use super::*;
+    use crate::test_utils::example_input;
+    use crate::types::{uint256_to_b256, MmrSnapshotCairo};
+
+    #[test]
+    fn reads_back_a_written_snapshot() {
+        let snapshot = example_input().end_snapshot;
+        let mut vm = VirtualMachine::new(false);
+        let address = vm.add_memory_segment();
+        let end = snapshot.to_memory(&mut vm, address).unwrap();
+
+        assert_eq!(
+            <MmrSnapshotCairo as CairoReadable>::n_fields(),
+            <MmrSnapshotCairo as CairoWritable>::n_fields()
+        );
+        assert_eq!(
+            (address + <MmrSnapshotCairo as CairoReadable>::n_fields()).unwrap(),
+            end
+        );
+
+        let read = <MmrSnapshotCairo as CairoReadable>::from_memory(&vm, address).unwrap();
+        assert_eq!(read.elements_count.0, Felt252::from(64));
+        assert_eq!(read.poseidon_root.0, snapshot.poseidon_root.0);
+        assert_eq!(
+            uint256_to_b256(&read.keccak_root),
+            uint256_to_b256(&snapshot.keccak_root)
+        );
+        assert_eq!(
+            read.poseidon_peaks
+                .iter()
+                .map(|peak| peak.0)
+                .collect::<Vec<_>>(),
+            snapshot
+                .poseidon_peaks
+                .iter()
+                .map(|peak| peak.0)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            read.keccak_peaks
+                .iter()
+                .map(uint256_to_b256)
+                .collect::<Vec<_>>(),
+            snapshot
+                .keccak_peaks
+                .iter()
+                .map(uint256_to_b256)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// Derived with the path of this module instead of the default one.
+    #[derive(CairoWritable, mmr_header_accumulator_derive::CairoReadable)]
+    #[cairo(readable = "super")]
+    struct Leaves {
+        root: Uint256,
+        #[cairo(with_len)]
+        leaves: Vec<Felt>,
+    }
+
+    #[test]
+    fn reads_back_with_a_custom_path() {
+        let leaves = Leaves {
+            root: Uint256(BigUint::from(7u8)),
+            leaves: vec![Felt(Felt252::from(1)), Felt(Felt252::from(2))],
+        };
+        let mut vm = VirtualMachine::new(false);
+        let address = vm.add_memory_segment();
+        leaves.to_memory(&mut vm, address).unwrap();
+
+        let read = <Leaves as CairoReadable>::from_memory(&vm, address).unwrap();
+        assert_eq!(read.root.0, leaves.root.0);
+        assert_eq!(
+            read.leaves.iter().map(|leaf| leaf.0).collect::<Vec<_>>(),
+            [Felt252::from(1), Felt252::from(2)]
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Runs the compiled programs on `example_input.json`.
+//!
+//! Needs the compiled programs, `make build-cairo` and `make build-cairo-proof` write them to
+//! `build/main.json` and `build/main_proof.json`. Other paths can be given with `MMR_PROGRAM` and `MMR_PROOF_PROGRAM`.
+//! Run with `cargo test -- --ignored`.
+
+use std::path::PathBuf;
+
+use cairo_vm_base::vm::cairo_vm::Felt252;
+use mmr_header_accumulator_hints::output::MmrUpdateOutput;
+use mmr_header_accumulator_hints::runner::{Runner, RunnerOptions};
+use mmr_header_accumulator_hints::types::{uint256_to_b256, BeaconMmrUpdateCairo};
+
+fn program_path(var: &str, file_name: &str) -> PathBuf {
+    std::env::var_os(var).map(PathBuf::from).unwrap_or_else(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../build")
+            .join(file_name)
+    })
+}
+
+fn example_input() -> BeaconMmrUpdateCairo {
+    serde_json::from_str(include_str!("../../example_input.json")).unwrap()
+}
+
+/// Output of the PIE run of the example input.
+fn pie_output() -> MmrUpdateOutput {
+    Runner::from_file(
+        &program_path("MMR_PROGRAM", "main.json"),
+        RunnerOptions::pie(),
+    )
+    .unwrap()
+    .run(example_input())
+    .unwrap()
+    .mmr_update_output()
+    .unwrap()
+}
+
+#[test]
+#[ignore = "needs the compiled program, see `make build-cairo`"]
+fn runs_the_example_input() {
+    let input = example_input();
+
+    let outcome = Runner::from_file(
+        &program_path("MMR_PROGRAM", "main.json"),
+        RunnerOptions::pie(),
+    )
+    .unwrap()
+    .run(input.clone())
+    .unwrap();
+
+    let end_snapshot = &outcome.end_snapshot;
+    let expected = &input.end_snapshot;
+    assert_eq!(end_snapshot.elements_count.0, Felt252::from(64));
+    assert_eq!(end_snapshot.elements_count.0, expected.elements_count.0);
+    assert_eq!(end_snapshot.poseidon_root.0, expected.poseidon_root.0);
+    assert_eq!(
+        uint256_to_b256(&end_snapshot.keccak_root),
+        uint256_to_b256(&expected.keccak_root)
+    );
+    assert_eq!(
+        end_snapshot
+            .poseidon_peaks
+            .iter()
+            .map(|peak| peak.0)
+            .collect::<Vec<_>>(),
+        expected
+            .poseidon_peaks
+            .iter()
+            .map(|peak| peak.0)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        end_snapshot
+            .keccak_peaks
+            .iter()
+            .map(uint256_to_b256)
+            .collect::<Vec<_>>(),
+        expected
+            .keccak_peaks
+            .iter()
+            .map(uint256_to_b256)
+            .collect::<Vec<_>>()
+    );
+
+    let output = outcome.mmr_update_output().unwrap();
+    assert_eq!(output.start_mmr_size, 1);
+    assert_eq!(output.end_mmr_size, 64);
+    assert_eq!(output.end_poseidon_root, expected.poseidon_root.0);
+    assert_eq!(
+        output.end_keccak_root,
+        uint256_to_b256(&expected.keccak_root)
+    );
+    assert_eq!(output.first_slot, 7954432);
+    assert_eq!(output.last_slot, 7954463);
+    assert_eq!(outcome.summary.n_headers, 32);
+}
+
+#[test]
+#[ignore = "needs the compiled proof mode program, see `make build-cairo-proof`"]
+fn writes_the_stwo_prover_inputs() {
+    let output_dir = std::env::temp_dir().join(format!(
+        "mmr-header-accumulator-stwo-{}",
+        std::process::id()
+    ));
+    if output_dir.exists() {
+        std::fs::remove_dir_all(&output_dir).unwrap();
+    }
+
+    let outcome = Runner::from_file(
+        &program_path("MMR_PROOF_PROGRAM", "main_proof.json"),
+        RunnerOptions::stwo(&output_dir),
+    )
+    .unwrap()
+    .run(example_input())
+    .unwrap();
+
+    assert!(outcome.pie.is_none());
+    let artifacts = &outcome.artifacts;
+    for (path, file_name) in [
+        (&artifacts.memory, "memory.bin"),
+        (&artifacts.trace, "trace.bin"),
+        (&artifacts.air_public_input, "air_public_inputs.json"),
+        (&artifacts.air_private_input, "air_private_inputs.json"),
+    ] {
+        let expected = output_dir.join(file_name);
+        assert_eq!(path.as_ref(), Some(&expected));
+        assert!(
+            std::fs::metadata(&expected).unwrap().len() > 0,
+            "{file_name}"
+        );
+    }
+
+    let public_input = std::fs::read_to_string(output_dir.join("air_public_inputs.json")).unwrap();
+    let output = MmrUpdateOutput::from_public_input_json(&public_input).unwrap();
+    assert_eq!(output, outcome.mmr_update_output().unwrap());
+    assert_eq!(output, pie_output());
+}
@@ -2,10 +2,17 @@
 
 PROGRAM_PATH=${1:-"src/beacon/main.cairo"}  # Default to main.cairo if no argument provided
 OUTPUT_NAME=$(basename "$PROGRAM_PATH" .cairo)  # Extract filename without path and extension
+EXTRA_FLAGS=""
+
+# Compile in proof mode when requested, e.g. for the Stwo prover
+if [ "$2" == "--proof_mode" ]; then
+    OUTPUT_NAME="${OUTPUT_NAME}_proof"
+    EXTRA_FLAGS="--proof_mode"
+fi
 
 echo "Compiling Cairo Program: $PROGRAM_PATH"
-cairo-compile "$PROGRAM_PATH" --output "build/${OUTPUT_NAME}.json"
+cairo-compile "$PROGRAM_PATH" --output "build/${OUTPUT_NAME}.json" $EXTRA_FLAGS
 
 if [ $? -eq 0 ]; then
     echo "Compilation Successful!"
-fi
\ No newline at end of file
+fi
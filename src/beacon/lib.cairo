@@ -3,7 +3,7 @@ from starkware.cairo.common.alloc import alloc
 from starkware.cairo.common.uint256 import Uint256, uint256_reverse_endian
 from starkware.cairo.common.dict_access import DictAccess
 from starkware.cairo.common.dict import dict_read
-from src.beacon.types import BeaconHeader
+from src.beacon.types import BeaconHeader, BeaconMmrUpdateOutput
 from src.core.ssz import SSZ
 from src.core.sha import SHA256
 from src.debug.lib import print_uint256, print_string
@@ -14,6 +14,7 @@ from src.mmr.utils import assert_is_last_leaf_in_mmr
 from src.mmr.core import hash_subtree_path_poseidon, hash_subtree_path_keccak
 
 func run_beacon_mmr_update{
+    output_ptr: felt*,
     range_check_ptr,
     bitwise_ptr: BitwiseBuiltin*,
     keccak_ptr: KeccakBuiltin*,
@@ -32,6 +33,17 @@ func run_beacon_mmr_update{
 
     %{ write_beacon_input() %}
 
+    // The first and last slots are 0 when no headers are added
+    local first_slot: felt;
+    local last_slot: felt;
+    if (n_headers == 0) {
+        assert first_slot = 0;
+        assert last_slot = 0;
+    } else {
+        assert first_slot = headers[0].slot;
+        assert last_slot = headers[n_headers - 1].slot;
+    }
+
     print_string('done');
     let (
         start_peaks_dict_poseidon,
@@ -58,7 +70,7 @@ func run_beacon_mmr_update{
         is_genesis = 0;
     }
 
-    assert_header_linkage(
+    let (local last_header_root: Uint256) = assert_header_linkage(
         previous_root=last_leaf_proof.header_root,
         headers=headers,
         count=n_headers,
@@ -67,7 +79,9 @@ func run_beacon_mmr_update{
         is_genesis=is_genesis,
     );
     with peaks_dict_poseidon, peaks_dict_keccak {
-        let (new_poseidon_root, new_keccak_root, new_mmr_size) = grow_mmr(
+        let (
+            new_poseidon_root, new_keccak_root, new_mmr_size, new_peaks_poseidon, new_peaks_keccak, new_peaks_len
+        ) = grow_mmr(
             mmr_size=start_mmr_snapshot.elements_count,
             keccak_leafs=keccak_hashes,
             poseidon_leafs=poseidon_hashes,
@@ -75,6 +89,16 @@ func run_beacon_mmr_update{
         );
     }
 
+    // The computed end state, read back by the runner along with the full peaks
+    local new_mmr_snapshot: MmrSnapshot = MmrSnapshot(
+        keccak_root=new_keccak_root,
+        poseidon_root=new_poseidon_root,
+        elements_count=new_mmr_size,
+        keccak_peaks=new_peaks_keccak,
+        poseidon_peaks=new_peaks_poseidon,
+        peaks_len=new_peaks_len,
+    );
+
     with peaks_dict_poseidon, peaks_dict_keccak {
         finalize_mmr(
             end_mmr_snapshot=end_mmr_snapshot,
@@ -88,13 +112,53 @@ func run_beacon_mmr_update{
         );
     }
 
-    return (
+    write_output(
+        start_mmr_snapshot=start_mmr_snapshot,
         new_keccak_root=new_keccak_root,
         new_poseidon_root=new_poseidon_root,
         new_mmr_size=new_mmr_size,
+        first_slot=first_slot,
+        last_slot=last_slot,
+        last_header_root=last_header_root,
+    );
+
+    %{ read_beacon_mmr_result() %}
+
+    return (
+        new_keccak_root=new_mmr_snapshot.keccak_root,
+        new_poseidon_root=new_mmr_snapshot.poseidon_root,
+        new_mmr_size=new_mmr_snapshot.elements_count,
     );
 }
 
+// Commits to the start and end state of the MMR, so a verifier can read them from the output
+func write_output{output_ptr: felt*}(
+    start_mmr_snapshot: MmrSnapshot,
+    new_keccak_root: Uint256,
+    new_poseidon_root: felt,
+    new_mmr_size: felt,
+    first_slot: felt,
+    last_slot: felt,
+    last_header_root: Uint256,
+) {
+    let output = cast(output_ptr, BeaconMmrUpdateOutput*);
+    assert output.start_keccak_root.low = start_mmr_snapshot.keccak_root.low;
+    assert output.start_keccak_root.high = start_mmr_snapshot.keccak_root.high;
+    assert output.start_poseidon_root = start_mmr_snapshot.poseidon_root;
+    assert output.start_mmr_size = start_mmr_snapshot.elements_count;
+    assert output.end_keccak_root.low = new_keccak_root.low;
+    assert output.end_keccak_root.high = new_keccak_root.high;
+    assert output.end_poseidon_root = new_poseidon_root;
+    assert output.end_mmr_size = new_mmr_size;
+    assert output.first_slot = first_slot;
+    assert output.last_slot = last_slot;
+    assert output.last_header_root.low = last_header_root.low;
+    assert output.last_header_root.high = last_header_root.high;
+
+    let output_ptr = output_ptr + BeaconMmrUpdateOutput.SIZE;
+    return ();
+}
+
 // To ensure we dont have any gaps in the MMR, we verify the proof of the last leaf of each MMR
 // this should then be the parent_root of the first header we add to the MMR
 func verify_last_leaf{
@@ -153,10 +217,10 @@ func assert_header_linkage{
     poseidon_hashes: felt*,
     keccak_hashes: Uint256*,
     is_genesis: felt,
-) {
+) -> (last_root: Uint256) {
     alloc_locals;
     if (count == 0) {
-        return ();
+        return (last_root=previous_root);
     }
 
     // For genesis, we need to skip the initial linkage check
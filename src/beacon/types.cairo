@@ -7,3 +7,16 @@ struct BeaconHeader {
     state_root: Uint256,
     body_root: Uint256,
 }
+
+// Public output of the program, written to the output builtin segment
+struct BeaconMmrUpdateOutput {
+    start_keccak_root: Uint256,
+    start_poseidon_root: felt,
+    start_mmr_size: felt,
+    end_keccak_root: Uint256,
+    end_poseidon_root: felt,
+    end_mmr_size: felt,
+    first_slot: felt,
+    last_slot: felt,
+    last_header_root: Uint256,
+}
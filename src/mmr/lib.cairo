@@ -94,7 +94,12 @@ func grow_mmr{
     peaks_dict_keccak: DictAccess*,
     pow2_array: felt*,
 }(mmr_size: felt, keccak_leafs: Uint256*, poseidon_leafs: felt*, n_headers: felt) -> (
-    new_mmr_root_poseidon: felt, new_mmr_root_keccak: Uint256, new_mmr_size: felt
+    new_mmr_root_poseidon: felt,
+    new_mmr_root_keccak: Uint256,
+    new_mmr_size: felt,
+    new_peaks_poseidon: felt*,
+    new_peaks_keccak: Uint256*,
+    new_peaks_len: felt,
 ) {
     let (mmr_array_keccak: Uint256*) = alloc();
     let (mmr_array_poseidon: felt*) = alloc();
@@ -106,13 +111,22 @@ func grow_mmr{
     }
 
     with mmr_array_poseidon, mmr_array_keccak, mmr_array_len, pow2_array, peaks_dict_poseidon, peaks_dict_keccak, mmr_offset {
-        let (new_mmr_root_poseidon: felt, new_mmr_root_keccak: Uint256) = get_roots();
+        let (
+            new_mmr_root_poseidon: felt,
+            new_mmr_root_keccak: Uint256,
+            new_peaks_poseidon: felt*,
+            new_peaks_keccak: Uint256*,
+            new_peaks_len: felt,
+        ) = get_roots();
     }
 
     return (
         new_mmr_root_poseidon=new_mmr_root_poseidon,
         new_mmr_root_keccak=new_mmr_root_keccak,
         new_mmr_size=mmr_array_len + mmr_offset,
+        new_peaks_poseidon=new_peaks_poseidon,
+        new_peaks_keccak=new_peaks_keccak,
+        new_peaks_len=new_peaks_len,
     );
 }
 
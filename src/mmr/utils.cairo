@@ -329,6 +329,9 @@ func get_full_mmr_peak_values{
 // Returns:
 // - root_poseidon: felt - root of the Poseidon MMR
 // - root_keccak: Uint256 - root of the Keccak MMR
+// - peaks_poseidon: felt* - peaks of the Poseidon MMR, from left to right
+// - peaks_keccak: Uint256* - peaks of the Keccak MMR, from left to right
+// - peaks_len: felt - number of peaks
 func get_roots{
     range_check_ptr,
     bitwise_ptr: BitwiseBuiltin*,
@@ -341,11 +344,17 @@ func get_roots{
     peaks_dict_poseidon: DictAccess*,
     peaks_dict_keccak: DictAccess*,
     mmr_offset: felt,
-}() -> (root_poseidon: felt, root_keccak: Uint256) {
+}() -> (
+    root_poseidon: felt,
+    root_keccak: Uint256,
+    peaks_poseidon: felt*,
+    peaks_keccak: Uint256*,
+    peaks_len: felt,
+) {
     alloc_locals;
     let mmr_size = mmr_offset + mmr_array_len;
-    let (peaks_positions: felt*, peaks_len: felt) = compute_peaks_positions(mmr_size);
-    let (peaks_poseidon: felt*, peaks_keccak: Uint256*) = get_peaks_from_positions{
+    let (peaks_positions: felt*, local peaks_len: felt) = compute_peaks_positions(mmr_size);
+    let (local peaks_poseidon: felt*, local peaks_keccak: Uint256*) = get_peaks_from_positions{
         peaks_positions=peaks_positions
     }(peaks_len);
     let (bagged_peaks_poseidon, bagged_peaks_keccak) = bag_peaks(
@@ -361,7 +370,7 @@ func get_roots{
     let (root_keccak: Uint256) = keccak(inputs=inputs_start, n_bytes=2 * 32);
     let (root_keccak) = uint256_reverse_endian(root_keccak);
 
-    return (root_poseidon, root_keccak);
+    return (root_poseidon, root_keccak, peaks_poseidon, peaks_keccak, peaks_len);
 }
 
 // Returns the peaks values from left to right for both MMRs given the peaks positions